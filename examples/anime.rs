@@ -26,7 +26,7 @@ fn main() {
         let rating = entry.get(5).unwrap().parse::<f32>().unwrap_or(0.0);
         ratings.insert(String::from(name), rating);
         recommender.add_object(&String::from(name));
-        let categories = categories_str.split(",");
+        let categories = categories_str.split(',');
         for cat in categories {
             let trimmed = cat.trim();
             recommender.add_tag(trimmed);
@@ -37,7 +37,7 @@ fn main() {
 
     let top_recommendations = recommender
         .object_recommendations(
-            &vec![
+            &[
                 String::from("Cowboy Bebop"),
                 String::from("Serial Experiments Lain"),
                 String::from("Ghost in the Shell"),
@@ -45,9 +45,7 @@ fn main() {
             15,
             5000,
             |_, _| 1.0,
-            |_, to| match to {
-                name => ratings.get(name).unwrap_or(&0.0).clone(),
-            },
+            |_, name| *ratings.get(name).unwrap_or(&0.0),
         )
         .iter()
         .take(10)
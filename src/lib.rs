@@ -57,4 +57,5 @@
 
 extern crate rand;
 
+pub mod prelude;
 pub mod recommender;
@@ -0,0 +1,230 @@
+//! # Prelude
+//!
+//! The `prelude` module re-exports the most commonly used types, so that
+//! they can be imported with a single `use` statement.
+//!
+//! ```
+//! use pixie_rust::prelude::*;
+//!
+//! let mut recommender: Recommender<String> = Recommender::new();
+//! recommender.tag_object(&String::from("The Raid"), "Action");
+//!
+//! let mut graph: Graph<RecommenderNode<String>> = Graph::new();
+//! graph.add_node(&RecommenderNode::Tag(String::from("Action")));
+//! ```
+
+pub use crate::recommender::digraph::DiGraph;
+pub use crate::recommender::early_stopping::EarlyStopping;
+pub use crate::recommender::early_stopping::WalkSchedule;
+pub use crate::recommender::explanation::AuditRow;
+pub use crate::recommender::explanation::PairExplanation;
+pub use crate::recommender::exploration::ExplorationStats;
+pub use crate::recommender::fingerprint::Fingerprint;
+pub use crate::recommender::fingerprint::FingerprintDiff;
+pub use crate::recommender::graph::Graph;
+pub use crate::recommender::graph::NodeEntry;
+pub use crate::recommender::graph::WalkResult;
+#[cfg(feature = "csv")]
+pub use crate::recommender::io::CsvColumns;
+pub use crate::recommender::memory_budget::BudgetPolicy;
+pub use crate::recommender::memory_budget::MemoryBudget;
+pub use crate::recommender::memory_budget::MemoryBudgetExceeded;
+pub use crate::recommender::ordered_graph::OrderedGraph;
+pub use crate::recommender::pagination::PageCursor;
+pub use crate::recommender::shared::SharedRecommender;
+pub use crate::recommender::similarity_index::SimilarityIndex;
+pub use crate::recommender::sketch::CountMinSketch;
+pub use crate::recommender::sketch::SketchConfig;
+pub use crate::recommender::sketch::TopKCounter;
+pub use crate::recommender::validation::ValidationReport;
+pub use crate::recommender::visit_model::VisitModel;
+pub use crate::recommender::AccumulationMode;
+pub use crate::recommender::FirstHopStrategy;
+pub use crate::recommender::Recommender;
+pub use crate::recommender::RecommenderNode;
+pub use crate::recommender::TieBreak;
+pub use crate::recommender::UnknownQuery;
+pub use crate::recommender::UnknownQueryPolicy;
+pub use crate::recommender::WalkAggregation;
+pub use crate::recommender::WeightPair;
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn prelude_exports() {
+        let mut recommender: Recommender<String> = Recommender::new();
+        recommender.tag_object(&String::from("The Raid"), "Action");
+        recommender.tag_object(&String::from("Rocky"), "Action");
+
+        let recommendations = recommender.recommendations(
+            &[RecommenderNode::Object(String::from("The Raid"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(recommendations.contains(&RecommenderNode::Tag(String::from("Action"))));
+
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+        assert_eq!(graph.max_degree(), 1);
+        let entry: NodeEntry<u32> = graph.node_entry(&3);
+        assert_eq!(entry.degree(), 0);
+        let walk: WalkResult<u32> = graph.random_walk_result(&3, 5, |_, _| 1.0);
+        assert!(walk.terminated_early);
+
+        assert_eq!(SketchConfig::new(0, 0, 1), SketchConfig::new(1, 1, 1));
+        let config = SketchConfig::new(16, 2, 1);
+        let mut sketch = CountMinSketch::new(16, 2);
+        sketch.increment(&1);
+        assert!(sketch.estimate(&1) >= 1);
+        let mut counter: TopKCounter<u32> = TopKCounter::new(&config);
+        counter.increment(1);
+        assert_eq!(counter.into_counts().len(), 1);
+
+        let stopping = EarlyStopping::new(1, 1).adaptive(true);
+        assert!(stopping.is_adaptive());
+        let mut schedule = WalkSchedule::new(3, &stopping);
+        schedule.record(0);
+        assert_eq!(schedule.walk_length(), 6);
+
+        let explanation: PairExplanation =
+            recommender.explain_pair(&String::from("The Raid"), &String::from("Rocky"));
+        assert_eq!(explanation.shared_tags, vec![String::from("Action")]);
+
+        let audit: Vec<AuditRow<String>> = recommender.audit(
+            &RecommenderNode::Tag(String::from("Action")),
+            3,
+            10,
+            42,
+            &WeightPair::uniform(),
+        );
+        assert!(!audit.is_empty());
+
+        let report: ValidationReport<String> = recommender.validate();
+        assert!(report.is_valid());
+
+        let (_, stats): (_, ExplorationStats) = recommender.recommendations_with_stats(
+            &[RecommenderNode::Tag(String::from("Action"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(stats.steps > 0);
+
+        let fingerprint: Fingerprint = recommender.structural_fingerprint();
+        let diff: FingerprintDiff = fingerprint.diff(&fingerprint);
+        assert!(diff.is_unchanged());
+
+        let recommendations = recommender.recommendations_per_query(
+            &[(
+                RecommenderNode::Tag(String::from("Action")),
+                WeightPair::uniform(),
+            )],
+            3,
+            10,
+        );
+        assert!(!recommendations.is_empty());
+
+        let model: VisitModel<String> = recommender.visit_model(
+            &[RecommenderNode::Tag(String::from("Action"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(!model.recommendations(|_| 1.0).is_empty());
+
+        let recommendations = recommender.recommendations_with_first_hop(
+            &[RecommenderNode::Tag(String::from("Action"))],
+            2,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            FirstHopStrategy::Uniform,
+        );
+        assert!(!recommendations.is_empty());
+
+        let recommendations = recommender.recommendations_with_mode(
+            &[RecommenderNode::Object(String::from("The Raid"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            AccumulationMode::TagsOnly,
+        );
+        assert_eq!(
+            recommendations,
+            vec![RecommenderNode::Tag(String::from("Action"))]
+        );
+
+        let recommendations = recommender.recommendations_with_aggregation(
+            &[RecommenderNode::Object(String::from("The Raid"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            WalkAggregation::VisitRate,
+        );
+        assert!(!recommendations.is_empty());
+
+        let recommendations = recommender.recommendations_with_unknown_queries(
+            &[RecommenderNode::Object(String::from("Rocky II"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            UnknownQueryPolicy::Error,
+        );
+        let error: UnknownQuery<String> = recommendations.unwrap_err();
+        assert_eq!(error.queries.len(), 1);
+
+        let recommendations = recommender.recommendations_with_tie_break(
+            &[RecommenderNode::Tag(String::from("Action"))],
+            3,
+            10,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            TieBreak::ObjectsFirst,
+        );
+        assert!(!recommendations.is_empty());
+
+        assert_eq!(PageCursor::first(10).page_size(), 10);
+
+        let budget = MemoryBudget::new(Some(1024), BudgetPolicy::TopK);
+        assert_eq!(budget.policy(), BudgetPolicy::TopK);
+        let exceeded = MemoryBudgetExceeded { max_candidates: 1 };
+        assert!(exceeded.to_string().contains('1'));
+
+        let index: SimilarityIndex<String> =
+            recommender.precompute_similarities(1, 3, 10, &WeightPair::uniform(), 42);
+        assert_eq!(index.len(), 2);
+
+        let mut ordered: OrderedGraph<u32> = OrderedGraph::new();
+        ordered.add_edge(&1, &2);
+        assert_eq!(ordered.degree(&1), 1);
+
+        let mut digraph: DiGraph<u32> = DiGraph::new();
+        digraph.add_edge(&1, &2);
+        assert!(digraph.transpose().contains_edge(&2, &1));
+
+        #[cfg(feature = "csv")]
+        {
+            let columns = CsvColumns::new(0, 1, ',');
+            let mut recommender: Recommender<String> = Recommender::new();
+            let data = "name,genre\nThe Raid,Action\n";
+            assert_eq!(
+                recommender
+                    .merge_from_csv(data.as_bytes(), &columns)
+                    .unwrap(),
+                1
+            );
+        }
+
+        let shared: SharedRecommender<String> = SharedRecommender::new(recommender);
+        assert_eq!(shared.load().validate().components, 1);
+    }
+}
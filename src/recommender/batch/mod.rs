@@ -0,0 +1,231 @@
+//! # Batch
+//!
+//! The `batch` module is a collection of utilities to compute
+//! recommendations for every object of a recommender, e.g. in offline jobs.
+
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Number of objects whose recommendations are computed in parallel
+/// before their lines are written.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_SIZE: usize = 1024;
+
+impl<T: Eq + Clone + Hash + fmt::Display> Recommender<T> {
+    /// Computes the best `limit` recommendations of every object and
+    /// writes them to `writer`, one object per line, in the format
+    /// `object\trecommendation_1,recommendation_2,...`.
+    ///
+    /// Each line is written as soon as it is computed, so the results are
+    /// never held in memory at the same time. The order of the lines is
+    /// unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    ///
+    /// let mut buffer: Vec<u8> = Vec::new();
+    /// recommender
+    ///     .write_all_recommendations(&mut buffer, 5, 3, 100, |_, _| 1.0, |_, _| 1.0)
+    ///     .unwrap();
+    ///
+    /// let output = String::from_utf8(buffer).unwrap();
+    /// assert!(output.contains("The Raid\tRocky\n"));
+    /// assert!(output.contains("Rocky\tThe Raid\n"));
+    /// ```
+    pub fn write_all_recommendations(
+        &self,
+        mut writer: impl io::Write,
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> io::Result<()> {
+        for node in self.graph.nodes() {
+            let obj = match node {
+                RecommenderNode::Object(obj) => obj,
+                RecommenderNode::Tag(_) => continue,
+            };
+            let line = self.recommendations_line(
+                obj,
+                limit,
+                depth,
+                max_total_steps,
+                &object_to_tag_weight,
+                &tag_to_object_weight,
+            );
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()
+    }
+
+    fn recommendations_line(
+        &self,
+        obj: &T,
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> String {
+        let recommendations = self
+            .object_recommendations(
+                std::slice::from_ref(obj),
+                depth,
+                max_total_steps,
+                object_to_tag_weight,
+                tag_to_object_weight,
+            )
+            .iter()
+            .take(limit)
+            .map(|rec| rec.to_string())
+            .collect::<Vec<String>>();
+        format!("{}\t{}", obj, recommendations.join(","))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Eq + Clone + Hash + fmt::Display + Send + Sync> Recommender<T> {
+    /// Same as
+    /// [`write_all_recommendations`](#method.write_all_recommendations),
+    /// but the recommendations of the objects are computed in parallel, on
+    /// rayon's thread pool.
+    ///
+    /// The objects are processed in chunks, so only the lines of a chunk
+    /// are held in memory at the same time.
+    ///
+    /// This method is only available with the `rayon` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    ///
+    /// let mut buffer: Vec<u8> = Vec::new();
+    /// recommender
+    ///     .write_all_recommendations_parallel(&mut buffer, 5, 3, 100, |_, _| 1.0, |_, _| 1.0)
+    ///     .unwrap();
+    ///
+    /// let output = String::from_utf8(buffer).unwrap();
+    /// assert!(output.contains("The Raid\tRocky\n"));
+    /// assert!(output.contains("Rocky\tThe Raid\n"));
+    /// ```
+    pub fn write_all_recommendations_parallel(
+        &self,
+        mut writer: impl io::Write,
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32 + Sync,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32 + Sync,
+    ) -> io::Result<()> {
+        let objects = self
+            .graph
+            .nodes()
+            .flat_map(|node| node.as_object())
+            .collect::<Vec<&T>>();
+        for chunk in objects.chunks(PARALLEL_CHUNK_SIZE) {
+            let lines = chunk
+                .par_iter()
+                .map(|obj| {
+                    self.recommendations_line(
+                        obj,
+                        limit,
+                        depth,
+                        max_total_steps,
+                        &object_to_tag_weight,
+                        &tag_to_object_weight,
+                    )
+                })
+                .collect::<Vec<String>>();
+            for line in lines {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_one_line_per_object() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, if obj % 2 == 0 { "Even" } else { "Odd" });
+        }
+        recommender.add_object(&100);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        recommender
+            .write_all_recommendations(&mut buffer, 3, 3, 100, |_, _| 1.0, |_, _| 1.0)
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        assert_eq!(output.lines().count(), 21);
+        assert!(output.lines().any(|line| line == "100\t"));
+        for line in output.lines() {
+            let mut fields = line.split('\t');
+            let obj = fields.next().unwrap().parse::<u32>().unwrap();
+            let recommendations = fields.next().unwrap();
+            if obj < 20 {
+                let recommendations = recommendations.split(',').collect::<Vec<&str>>();
+                assert_eq!(recommendations.len(), 3);
+                for rec in recommendations {
+                    assert_eq!(rec.parse::<u32>().unwrap() % 2, obj % 2);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn write_one_line_per_object_in_parallel() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..3000 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+        }
+
+        let mut buffer: Vec<u8> = Vec::new();
+        recommender
+            .write_all_recommendations_parallel(&mut buffer, 2, 3, 50, |_, _| 1.0, |_, _| 1.0)
+            .unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        // The objects span multiple chunks, and each one is written once
+        let mut objects = output
+            .lines()
+            .map(|line| line.split('\t').next().unwrap().parse::<u32>().unwrap())
+            .collect::<Vec<u32>>();
+        objects.sort_unstable();
+        assert_eq!(objects, (0..3000).collect::<Vec<u32>>());
+        for line in output.lines() {
+            let (obj, recommendations) = line.split_once('\t').unwrap();
+            let obj = obj.parse::<u32>().unwrap();
+            for rec in recommendations.split(',') {
+                assert_eq!(rec.parse::<u32>().unwrap() % 3, obj % 3);
+            }
+        }
+    }
+}
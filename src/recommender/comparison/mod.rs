@@ -0,0 +1,128 @@
+//! # Comparison
+//!
+//! The `comparison` module is a collection of utilities to measure how
+//! much recommendations change, e.g. between two versions of a dataset.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use super::Recommender;
+use super::WeightPair;
+
+/// Computes the Jaccard index of two lists of recommendations, ignoring
+/// their order.
+///
+/// The result goes from `0.0` (nothing in common) to `1.0` (the same
+/// recommendations). Two empty lists are considered equal.
+///
+/// # Examples
+///
+/// ```
+/// use pixie_rust::recommender::comparison::recommendation_overlap;
+///
+/// assert_eq!(recommendation_overlap(&[1, 2, 3], &[3, 2, 1]), 1.0);
+/// assert_eq!(recommendation_overlap(&[1, 2], &[2, 3]), 1.0 / 3.0);
+/// assert_eq!(recommendation_overlap(&[1], &[2]), 0.0);
+/// ```
+pub fn recommendation_overlap<T: Eq + Hash>(a: &[T], b: &[T]) -> f64 {
+    let set_a: HashSet<&T> = a.iter().collect();
+    let set_b: HashSet<&T> = b.iter().collect();
+    let union = set_a.union(&set_b).count();
+    if union == 0 {
+        1.0
+    } else {
+        set_a.intersection(&set_b).count() as f64 / union as f64
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Computes the best `limit` object recommendations for the same
+    /// queries on this and on the `other` recommender, and returns their
+    /// [`recommendation_overlap`].
+    ///
+    /// [`recommendation_overlap`]: comparison/fn.recommendation_overlap.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::WeightPair;
+    ///
+    /// let mut old_recommender: Recommender<String> = Recommender::new();
+    /// let mut new_recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// old_recommender.tag_object(&raid, "Action");
+    /// old_recommender.tag_object(&rocky, "Action");
+    /// new_recommender.tag_object(&raid, "Action");
+    /// new_recommender.tag_object(&python, "Action");
+    ///
+    /// let overlap = old_recommender.recommendation_overlap_with(
+    ///     &new_recommender,
+    ///     &[raid],
+    ///     10,
+    ///     3,
+    ///     100,
+    ///     &WeightPair::uniform()
+    /// );
+    ///
+    /// assert_eq!(overlap, 0.0);
+    /// ```
+    pub fn recommendation_overlap_with(
+        &self,
+        other: &Recommender<T>,
+        queries: &[T],
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        weights: &WeightPair<T>,
+    ) -> f64 {
+        let top_recommendations = |recommender: &Recommender<T>| -> Vec<T> {
+            recommender
+                .object_recommendations_filtered(
+                    queries,
+                    depth,
+                    max_total_steps,
+                    |obj, tag| (weights.object_to_tag)(obj, tag),
+                    |tag, obj| (weights.tag_to_object)(tag, obj),
+                    |_| true,
+                )
+                .into_iter()
+                .take(limit)
+                .collect()
+        };
+        recommendation_overlap(&top_recommendations(self), &top_recommendations(other))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compare_recommenders() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        let mut identical: Recommender<u32> = Recommender::new();
+        let mut divergent: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+            identical.tag_object(&obj, &format!("{}", obj % 2));
+            divergent.tag_object(&obj, &format!("{}", obj % 3));
+        }
+        let weights = WeightPair::uniform();
+
+        let overlap =
+            recommender.recommendation_overlap_with(&identical, &[0], 20, 3, 2000, &weights);
+        assert_eq!(overlap, 1.0);
+
+        let overlap =
+            recommender.recommendation_overlap_with(&divergent, &[0], 20, 3, 2000, &weights);
+        assert!(overlap < 0.5, "Overlap of {}", overlap);
+        assert!(overlap > 0.0, "Overlap of {}", overlap);
+
+        assert_eq!(recommendation_overlap::<u32>(&[], &[]), 1.0);
+    }
+}
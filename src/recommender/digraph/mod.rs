@@ -0,0 +1,122 @@
+//! # Directed Graph
+//!
+//! The `digraph` module is a collection of utilities to handle a
+//! directed graph structure, such as "viewed before" relationships.
+//!
+//! The end user of the library should not need to use this module
+//! directly.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+
+/// Data structure containing a directed graph.
+pub struct DiGraph<T> {
+    data: HashMap<T, HashSet<T>>,
+}
+
+impl<T: Eq + Clone + Hash> DiGraph<T> {
+    /// Creates an empty graph
+    pub fn new() -> DiGraph<T> {
+        DiGraph {
+            data: HashMap::new(),
+        }
+    }
+
+    /// Adds a node to the graph.
+    pub fn add_node(&mut self, node: &T) {
+        self.data.entry(node.clone()).or_default();
+    }
+
+    /// Adds an edge from `from` to `to`. The nodes are created, if needed.
+    pub fn add_edge(&mut self, from: &T, to: &T) {
+        self.add_node(to);
+        self.data
+            .entry(from.clone())
+            .or_default()
+            .insert(to.clone());
+    }
+
+    /// Checks if there is an edge from `from` to `to`.
+    pub fn contains_edge(&self, from: &T, to: &T) -> bool {
+        self.data
+            .get(from)
+            .map(|succs| succs.contains(to))
+            .unwrap_or(false)
+    }
+
+    /// Returns the nodes that can be reached from a node with a single edge.
+    pub fn successors(&self, node: &T) -> HashSet<T> {
+        self.data.get(node).cloned().unwrap_or_default()
+    }
+
+    /// Returns a new graph with the same nodes and all edges reversed.
+    ///
+    /// This can be used to answer "what leads to this node" queries, such
+    /// as the objects that are commonly viewed before another one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::digraph::DiGraph;
+    ///
+    /// let mut graph: DiGraph<u32> = DiGraph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// let transposed = graph.transpose();
+    ///
+    /// assert!(transposed.contains_edge(&2, &1));
+    /// assert!(!transposed.contains_edge(&1, &2));
+    /// ```
+    pub fn transpose(&self) -> DiGraph<T> {
+        let mut transposed = DiGraph::new();
+        for (from, succs) in self.data.iter() {
+            transposed.add_node(from);
+            for to in succs {
+                transposed.add_edge(to, from);
+            }
+        }
+        transposed
+    }
+}
+
+impl<T: Eq + Clone + Hash> Default for DiGraph<T> {
+    fn default() -> DiGraph<T> {
+        DiGraph::new()
+    }
+}
+
+impl<T: fmt::Debug + Eq + Hash> fmt::Debug for DiGraph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DiGraph {:?}", self.data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transpose_graph() {
+        let mut graph: DiGraph<&str> = DiGraph::new();
+        graph.add_edge(&"a", &"b");
+        graph.add_edge(&"a", &"c");
+        graph.add_edge(&"c", &"c");
+        graph.add_node(&"d");
+
+        let transposed = graph.transpose();
+        assert!(transposed.contains_edge(&"b", &"a"));
+        assert!(transposed.contains_edge(&"c", &"a"));
+        assert!(transposed.contains_edge(&"c", &"c"));
+        assert!(!transposed.contains_edge(&"a", &"b"));
+        assert!(transposed.successors(&"a").is_empty());
+        assert!(transposed.successors(&"d").is_empty());
+        assert_eq!(transposed.data.len(), 4);
+
+        let twice = transposed.transpose();
+        for node in ["a", "b", "c", "d"].iter() {
+            assert_eq!(twice.successors(node), graph.successors(node));
+        }
+    }
+}
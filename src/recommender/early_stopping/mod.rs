@@ -0,0 +1,117 @@
+//! # Early Stopping
+//!
+//! The `early_stopping` module is a collection of utilities to stop the
+//! random walks as soon as enough good candidates were found.
+//!
+//! The end user of the library should only need the [`EarlyStopping`]
+//! from this module.
+//!
+//! [`EarlyStopping`]: struct.EarlyStopping.html
+
+/// Configuration of the early stopping of the random walks.
+///
+/// The walks of a query stop once at least `min_candidates` distinct
+/// nodes (other than the query) have been visited at least `min_visits`
+/// times, even if the step budget was not exhausted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EarlyStopping {
+    min_visits: u32,
+    min_candidates: usize,
+    adaptive: bool,
+}
+
+impl EarlyStopping {
+    /// Creates a new configuration, with a fixed walk length.
+    pub fn new(min_visits: u32, min_candidates: usize) -> EarlyStopping {
+        EarlyStopping {
+            min_visits: min_visits.max(1),
+            min_candidates,
+            adaptive: false,
+        }
+    }
+
+    /// Enables or disables the adaptive walk scheduling.
+    ///
+    /// When enabled, the walk length is increased every time a walk
+    /// does not find any new candidate, and reset to the requested depth
+    /// once new candidates are found again. This reaches the threshold
+    /// with fewer steps on sparse regions of the graph, at the cost of
+    /// less local recommendations.
+    pub fn adaptive(self, adaptive: bool) -> EarlyStopping {
+        EarlyStopping { adaptive, ..self }
+    }
+
+    /// Number of visits that a node needs to be considered a candidate.
+    pub fn min_visits(&self) -> u32 {
+        self.min_visits
+    }
+
+    /// Number of candidates required to stop the walks.
+    pub fn min_candidates(&self) -> usize {
+        self.min_candidates
+    }
+
+    /// Whether the adaptive walk scheduling is enabled.
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive
+    }
+}
+
+/// Decides the length of the next walk, based on the progress so far.
+pub struct WalkSchedule {
+    depth: u8,
+    current: u8,
+    adaptive: bool,
+}
+
+impl WalkSchedule {
+    /// Creates a new schedule, starting with walks of `depth` nodes.
+    pub fn new(depth: u8, config: &EarlyStopping) -> WalkSchedule {
+        WalkSchedule {
+            depth,
+            current: depth,
+            adaptive: config.adaptive,
+        }
+    }
+
+    /// Length of the next walk.
+    pub fn walk_length(&self) -> u8 {
+        self.current
+    }
+
+    /// Updates the schedule with the number of candidates found by the
+    /// last walk.
+    pub fn record(&mut self, new_candidates: usize) {
+        if !self.adaptive {
+            return;
+        }
+        self.current = if new_candidates == 0 {
+            self.current.saturating_add(self.depth)
+        } else {
+            self.depth
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_schedule() {
+        let mut schedule = WalkSchedule::new(3, &EarlyStopping::new(2, 10));
+        schedule.record(0);
+        schedule.record(0);
+        assert_eq!(schedule.walk_length(), 3);
+    }
+
+    #[test]
+    fn adaptive_schedule() {
+        let mut schedule = WalkSchedule::new(3, &EarlyStopping::new(2, 10).adaptive(true));
+        schedule.record(0);
+        schedule.record(0);
+        assert_eq!(schedule.walk_length(), 9);
+        schedule.record(1);
+        assert_eq!(schedule.walk_length(), 3);
+    }
+}
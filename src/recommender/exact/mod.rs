@@ -0,0 +1,256 @@
+//! # Exact
+//!
+//! The `exact` module is a collection of utilities to compute exact
+//! recommendations on small graphs, without random walks.
+//!
+//! The scores are computed with a personalized PageRank, which is the
+//! distribution that the random walks approximate, so the rankings are
+//! similar, but fully reproducible.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::graph::sanitize_weight;
+use super::Recommender;
+use super::RecommenderNode;
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-12;
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Computes the best `k` object recommendations by iteratively
+    /// propagating the scores of the queries over the whole graph,
+    /// instead of sampling random walks.
+    ///
+    /// On each iteration, a fraction `restart_probability` of the score
+    /// goes back to the queries, while the rest is split among the
+    /// neighbors of each node according to the weight functions. This is
+    /// repeated until the scores converge.
+    ///
+    /// Every iteration goes through all the edges of the graph, so this
+    /// is only advised for small graphs (e.g. a few thousand objects).
+    /// For the same recommender, the results are always the same, and
+    /// objects with the same score are ranked in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let recommendations = recommender.object_recommendations_topk_exact(
+    ///     &[raid],
+    ///     10,
+    ///     0.3,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![rocky, creed]);
+    /// ```
+    pub fn object_recommendations_topk_exact(
+        &self,
+        queries: &[T],
+        k: usize,
+        restart_probability: f64,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        // The nodes are sorted (objects in insertion order and tags by
+        // name), so that the scores don't depend on the order of the
+        // graph's hash maps.
+        let mut nodes = self.graph.nodes().collect::<Vec<&RecommenderNode<T>>>();
+        nodes.sort_by(|node_a, node_b| match (node_a, node_b) {
+            (RecommenderNode::Object(a), RecommenderNode::Object(b)) => {
+                self.insertion_rank(a).cmp(&self.insertion_rank(b))
+            }
+            (RecommenderNode::Tag(a), RecommenderNode::Tag(b)) => a.cmp(b),
+            (RecommenderNode::Object(_), RecommenderNode::Tag(_)) => Ordering::Less,
+            (RecommenderNode::Tag(_), RecommenderNode::Object(_)) => Ordering::Greater,
+        });
+        let index = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (*node, i))
+            .collect::<HashMap<&RecommenderNode<T>, usize>>();
+
+        let mut restart = vec![0.0; nodes.len()];
+        let query_indices = queries
+            .iter()
+            .flat_map(|q| index.get(&RecommenderNode::Object(q.clone())))
+            .cloned()
+            .collect::<Vec<usize>>();
+        for i in query_indices.iter() {
+            restart[*i] = 1.0 / query_indices.len() as f64;
+        }
+
+        // Transition probabilities of each node, sorted by neighbor, so
+        // that the sums are always computed in the same order.
+        let transitions = nodes
+            .iter()
+            .map(|node| {
+                let mut edges = self
+                    .graph
+                    .successors(node)
+                    .iter()
+                    .map(|succ| {
+                        let weight = self.walk_weight(
+                            node,
+                            succ,
+                            &object_to_tag_weight,
+                            &tag_to_object_weight,
+                        );
+                        (index[succ], f64::from(sanitize_weight(weight)))
+                    })
+                    .filter(|(_, weight)| *weight > 0.0)
+                    .collect::<Vec<(usize, f64)>>();
+                edges.sort_by_key(|(succ, _)| *succ);
+                let total_weight: f64 = edges.iter().map(|(_, weight)| weight).sum();
+                for edge in edges.iter_mut() {
+                    edge.1 /= total_weight;
+                }
+                edges
+            })
+            .collect::<Vec<Vec<(usize, f64)>>>();
+
+        let restart_probability = if restart_probability.is_nan() {
+            1.0
+        } else {
+            restart_probability.clamp(0.0, 1.0)
+        };
+        let mut scores = restart.clone();
+        for _ in 0..MAX_ITERATIONS {
+            let mut next_scores = restart
+                .iter()
+                .map(|r| r * restart_probability)
+                .collect::<Vec<f64>>();
+            for (i, edges) in transitions.iter().enumerate() {
+                let propagated = scores[i] * (1.0 - restart_probability);
+                if edges.is_empty() {
+                    // Dead ends restart the walk
+                    for (j, r) in restart.iter().enumerate() {
+                        next_scores[j] += propagated * r;
+                    }
+                } else {
+                    for (j, probability) in edges.iter() {
+                        next_scores[*j] += propagated * probability;
+                    }
+                }
+            }
+            let change: f64 = scores
+                .iter()
+                .zip(next_scores.iter())
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+            scores = next_scores;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+
+        let is_blocked = self.blocked_objects();
+        let mut ranked = nodes
+            .iter()
+            .zip(scores.iter())
+            .enumerate()
+            .filter(|(i, (_, score))| **score > 0.0 && !query_indices.contains(i))
+            .flat_map(|(_, (node, score))| match node {
+                RecommenderNode::Object(obj) if !is_blocked(obj) => Some((obj, *score)),
+                _ => None,
+            })
+            .collect::<Vec<(&T, f64)>>();
+        ranked.sort_by(|(obj_a, a), (obj_b, b)| {
+            b.partial_cmp(a)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.insertion_rank(obj_a).cmp(&self.insertion_rank(obj_b)))
+        });
+        ranked
+            .into_iter()
+            .take(k)
+            .map(|(obj, _)| obj.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deterministic_topk() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let creed = String::from("Creed");
+        let python = String::from("Monty Python and The Holy Grail");
+        let brian = String::from("Life of Brian");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&raid, "Martial Arts");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&creed, "Drama");
+        recommender.tag_object(&python, "Comedy");
+        recommender.tag_object(&brian, "Comedy");
+
+        let topk = |k: usize| {
+            recommender.object_recommendations_topk_exact(
+                std::slice::from_ref(&raid),
+                k,
+                0.3,
+                |_, _| 1.0,
+                |_, _| 1.0,
+            )
+        };
+
+        let recommendations = topk(10);
+        assert_eq!(recommendations, vec![rocky.clone(), creed]);
+        for _ in 0..10 {
+            assert_eq!(topk(10), recommendations);
+        }
+        assert_eq!(topk(1), vec![rocky]);
+        assert!(topk(0).is_empty());
+
+        let recommendations = recommender.object_recommendations_topk_exact(
+            &[python],
+            10,
+            0.3,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations, vec![brian]);
+
+        let recommendations =
+            recommender.object_recommendations_topk_exact(&[raid], 10, 0.3, |_, _| 0.0, |_, _| 0.0);
+        assert!(recommendations.is_empty());
+    }
+
+    #[test]
+    fn ties_follow_insertion_order() {
+        for _ in 0..20 {
+            let mut recommender: Recommender<u32> = Recommender::new();
+            for obj in 0..50 {
+                recommender.tag_object(&obj, &format!("{}", obj % 5));
+                recommender.tag_object(&obj, &format!("Group {}", obj % 3));
+            }
+
+            let recommendations =
+                recommender.object_recommendations_topk_exact(&[0], 3, 0.3, |_, _| 1.0, |_, _| 1.0);
+            assert_eq!(recommendations, vec![15, 30, 45]);
+        }
+    }
+}
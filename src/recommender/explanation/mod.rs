@@ -0,0 +1,377 @@
+//! # Explanation
+//!
+//! The `explanation` module is a collection of utilities to describe
+//! why objects are related.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::Recommender;
+use super::RecommenderNode;
+use super::WeightPair;
+
+/// Number of steps used to estimate the connection strength of a pair.
+const PAIR_STEPS: usize = 1000;
+
+/// Number of connecting tags returned for each rich recommendation.
+const CONNECTING_TAGS: usize = 3;
+
+/// Description of the relationship between two objects.
+#[derive(PartialEq, Clone, Debug)]
+pub struct PairExplanation {
+    /// Tags assigned to both objects, in alphabetical order.
+    pub shared_tags: Vec<String>,
+    /// Estimated probability of a short walk from the first object
+    /// ending in the second one.
+    pub co_visitation: f64,
+}
+
+/// Row of an audit, describing how the walks reached a candidate.
+#[derive(PartialEq, Clone, Debug)]
+pub struct AuditRow<T> {
+    /// Candidate node.
+    pub node: RecommenderNode<T>,
+    /// Fraction of all visits that went to this candidate.
+    pub score: f64,
+    /// Number of times the candidate was visited.
+    pub visits: u32,
+    /// Number of walks that visited the candidate at least once.
+    pub walks: u32,
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Describes why two objects are related, returning the tags that they
+    /// share and an estimate of how strongly they are connected.
+    ///
+    /// The estimate is obtained from uniform walks that go from `a`
+    /// to one of its tags and then to one of the objects of that tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    ///
+    /// let explanation = recommender.explain_pair(&rocky, &raid);
+    ///
+    /// assert_eq!(explanation.shared_tags, vec![String::from("Action")]);
+    /// assert!(explanation.co_visitation > 0.0);
+    /// ```
+    pub fn explain_pair(&self, a: &T, b: &T) -> PairExplanation {
+        let node_a = RecommenderNode::Object(a.clone());
+        let node_b = RecommenderNode::Object(b.clone());
+
+        let tags_b: HashSet<RecommenderNode<T>> = self.graph.successors(&node_b);
+        let mut shared_tags = self
+            .graph
+            .successors(&node_a)
+            .into_iter()
+            .filter(|node| tags_b.contains(node))
+            .flat_map(|node| match node {
+                RecommenderNode::Tag(tag) => Some(tag),
+                RecommenderNode::Object(_) => None,
+            })
+            .collect::<Vec<String>>();
+        shared_tags.sort();
+
+        let mut walks = 0;
+        let mut hits = 0;
+        Recommender::accumulate_walks(
+            PAIR_STEPS,
+            || self.graph.random_walk(&node_a, 3, |_, _| 1.0),
+            |visits| {
+                walks += 1;
+                if visits.len() == 3 && visits.front() == Some(&node_b) {
+                    hits += 1;
+                }
+            },
+        );
+        let co_visitation = if walks == 0 {
+            0.0
+        } else {
+            f64::from(hits) / f64::from(walks)
+        };
+
+        PairExplanation {
+            shared_tags,
+            co_visitation,
+        }
+    }
+
+    /// Lists every candidate reached by the walks from a query, along with
+    /// its score, visits and how many walks reached it.
+    ///
+    /// The rows are sorted by visits, then by walks and then by the order
+    /// in which the candidates were first visited, so for the same
+    /// recommender, arguments and `seed`, the audit is always the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::WeightPair;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let query = RecommenderNode::Object(raid);
+    /// let weights = WeightPair::uniform();
+    /// let audit = recommender.audit(&query, 3, 300, 42, &weights);
+    ///
+    /// assert_eq!(audit[0].node, RecommenderNode::Tag(String::from("Action")));
+    /// assert_eq!(audit[0].walks, 100);
+    /// assert_eq!(audit, recommender.audit(&query, 3, 300, 42, &weights));
+    /// ```
+    pub fn audit(
+        &self,
+        query: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        seed: u64,
+        weights: &WeightPair<T>,
+    ) -> Vec<AuditRow<T>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut visit_order: Vec<RecommenderNode<T>> = Vec::new();
+        let mut counts: HashMap<RecommenderNode<T>, (u32, u32)> = HashMap::new();
+        let mut total_visits = 0;
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || {
+                self.graph.random_walk_with_rng(
+                    query,
+                    depth,
+                    |from, to| {
+                        self.walk_weight(from, to, &weights.object_to_tag, &weights.tag_to_object)
+                    },
+                    &mut rng,
+                )
+            },
+            |visits| {
+                let mut walk_nodes: HashSet<&RecommenderNode<T>> = HashSet::new();
+                for visited in visits.iter() {
+                    total_visits += 1;
+                    let entry = counts.entry(visited.clone()).or_insert_with_key(|node| {
+                        visit_order.push(node.clone());
+                        (0, 0)
+                    });
+                    entry.0 += 1;
+                    if walk_nodes.insert(visited) {
+                        entry.1 += 1;
+                    }
+                }
+            },
+        );
+
+        let mut rows = visit_order
+            .into_iter()
+            .filter(|node| node != query)
+            .map(|node| {
+                let (visits, walks) = counts[&node];
+                AuditRow {
+                    node,
+                    score: f64::from(visits) / f64::from(total_visits),
+                    visits,
+                    walks,
+                }
+            })
+            .collect::<Vec<AuditRow<T>>>();
+        // The sort is stable, so ties keep the visit order
+        rows.sort_by(|a, b| b.visits.cmp(&a.visits).then(b.walks.cmp(&a.walks)));
+        rows
+    }
+
+    /// Receives a set of queries (that can only be objects) and returns
+    /// an ordered sequence of recommendations (with the first one being
+    /// the "best" one), each one along with its top connecting tags.
+    ///
+    /// The connecting tags of an object are the tags that the walks went
+    /// through right before visiting it, with the fraction of those
+    /// visits that came from each tag. At most 3 tags are returned per
+    /// object, from the most to the least visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_rich(
+    ///     &[raid],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(
+    ///     recommendations,
+    ///     vec![(rocky, vec![(String::from("Action"), 1.0)])]
+    /// );
+    /// ```
+    pub fn object_recommendations_rich(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<(T, Vec<(String, f64)>)> {
+        let (all_recommendations, via_tags) = self.tagged_aggregated_recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        );
+
+        self.rank_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => false,
+            RecommenderNode::Object(obj) => !queries.contains(obj),
+        })
+        .into_iter()
+        .flat_map(|node| {
+            let mut tags = via_tags
+                .get(&node)
+                .map(|tags| tags.iter().collect::<Vec<(&String, &u32)>>())
+                .unwrap_or_default();
+            tags.sort_by(|(tag_a, a), (tag_b, b)| b.cmp(a).then_with(|| tag_a.cmp(tag_b)));
+            let total_visits: u32 = tags.iter().map(|(_, count)| **count).sum();
+            let connecting_tags = tags
+                .into_iter()
+                .take(CONNECTING_TAGS)
+                .map(|(tag, count)| (tag.clone(), f64::from(*count) / f64::from(total_visits)))
+                .collect();
+            match node {
+                RecommenderNode::Object(obj) => Some((obj, connecting_tags)),
+                RecommenderNode::Tag(_) => None,
+            }
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn explain_related_movies() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let python = String::from("Monty Python and The Holy Grail");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&raid, "Martial Arts");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&python, "Comedy");
+
+        let explanation = recommender.explain_pair(&rocky, &raid);
+        assert_eq!(explanation.shared_tags, vec![String::from("Action")]);
+        assert!(explanation.co_visitation > 0.1 && explanation.co_visitation < 0.4);
+
+        let explanation = recommender.explain_pair(&rocky, &python);
+        assert!(explanation.shared_tags.is_empty());
+        assert_eq!(explanation.co_visitation, 0.0);
+    }
+
+    #[test]
+    fn rich_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..30 {
+            for tag in 0..5 {
+                if obj % (tag + 2) == 0 {
+                    recommender.tag_object(&obj, &format!("{}", tag));
+                }
+            }
+            recommender.tag_object(&obj, &format!("{}", obj % 3 + 10));
+        }
+
+        let recommendations =
+            recommender.object_recommendations_rich(&[60], 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(recommendations.is_empty());
+
+        let query = 0;
+        let query_tags = recommender
+            .graph
+            .successors(&RecommenderNode::Object(query));
+        let recommendations =
+            recommender.object_recommendations_rich(&[query], 3, 10000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.is_empty());
+        for (obj, connecting_tags) in recommendations.iter() {
+            assert_ne!(*obj, query);
+            assert!(!connecting_tags.is_empty() && connecting_tags.len() <= 3);
+            let obj_tags = recommender.graph.successors(&RecommenderNode::Object(*obj));
+            for (tag, share) in connecting_tags.iter() {
+                let tag_node = RecommenderNode::Tag(tag.clone());
+                assert!(query_tags.contains(&tag_node));
+                assert!(obj_tags.contains(&tag_node));
+                assert!(*share > 0.0 && *share <= 1.0);
+            }
+            for pair in connecting_tags.windows(2) {
+                assert!(pair[0].1 >= pair[1].1);
+            }
+        }
+    }
+
+    #[test]
+    fn reproducible_audit() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..50 {
+            recommender.tag_object(&obj, &format!("{}", obj % 4));
+            recommender.tag_object(&obj, &format!("{}", obj % 6 + 10));
+        }
+        let query = RecommenderNode::Object(0);
+        let weights = WeightPair::uniform();
+
+        let audit = recommender.audit(&query, 5, 5000, 500, &weights);
+        let dump = format!("{:?}", audit);
+        assert_eq!(
+            format!("{:?}", recommender.audit(&query, 5, 5000, 500, &weights)),
+            dump
+        );
+        assert_ne!(
+            format!("{:?}", recommender.audit(&query, 5, 5000, 501, &weights)),
+            dump
+        );
+
+        assert!(audit.iter().all(|row| row.node != query));
+        for row in audit.iter() {
+            assert!(row.walks > 0 && row.walks <= row.visits);
+            assert!(row.score > 0.0 && row.score < 1.0);
+        }
+        for pair in audit.windows(2) {
+            assert!((pair[0].visits, pair[0].walks) >= (pair[1].visits, pair[1].walks));
+        }
+        assert!(recommender
+            .audit(&RecommenderNode::Object(100), 5, 5000, 500, &weights)
+            .is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+//! # Exploration
+//!
+//! The `exploration` module is a collection of utilities to check if the
+//! walks explored enough of the graph, which helps tuning the
+//! `max_total_steps` of each call.
+
+use std::cell::Cell;
+use std::hash::Hash;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Minimum fraction of its reachable nodes that a query must visit to be
+/// considered explored.
+const MIN_COVERAGE: f64 = 0.5;
+
+/// Statistics about how much of the graph the walks of a call explored.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExplorationStats {
+    /// Total number of steps taken by the walks.
+    pub steps: usize,
+    /// Number of distinct nodes (other than the queries) visited.
+    pub candidates: usize,
+    /// Number of queries that visited less than half of the nodes that
+    /// they can reach within the walk depth.
+    pub under_explored_queries: usize,
+    /// Whether the majority of the queries were under-explored, which
+    /// suggests that `max_total_steps` was too small.
+    pub under_explored: bool,
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Same as [`recommendations`], but also returns statistics about how
+    /// much of the graph the walks explored.
+    ///
+    /// Each query is compared against the set of nodes that its walks can
+    /// reach with the given `depth`. Computing that set requires a search
+    /// over the neighborhood of each query, so this is slower than
+    /// [`recommendations`] and is meant to tune the walk budget.
+    ///
+    /// [`recommendations`]: struct.Recommender.html#method.recommendations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let (recommendations, stats) = recommender.recommendations_with_stats(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert_eq!(stats.candidates, 2);
+    /// assert!(!stats.under_explored);
+    /// ```
+    pub fn recommendations_with_stats(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> (Vec<RecommenderNode<T>>, ExplorationStats) {
+        let steps = Cell::new(0);
+        let known_queries = Cell::new(0);
+        let under_explored_queries = Cell::new(0);
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, max_steps| {
+                let counts = self.recommendations_map(q, depth, max_steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                });
+                if self.graph.contains(q) {
+                    known_queries.set(known_queries.get() + 1);
+                    let visited = counts.keys().filter(|node| *node != q).count();
+                    let reachable = self.reachable_nodes(q, usize::from(depth.saturating_sub(1)));
+                    if (visited as f64) < reachable as f64 * MIN_COVERAGE {
+                        under_explored_queries.set(under_explored_queries.get() + 1);
+                    }
+                }
+                steps.set(steps.get() + counts.values().map(|c| *c as usize).sum::<usize>());
+                counts
+            });
+
+        let recommendations =
+            self.rank_recommendations(&all_recommendations, |node| !queries.contains(node));
+        let stats = ExplorationStats {
+            steps: steps.get(),
+            candidates: all_recommendations
+                .keys()
+                .filter(|node| !queries.contains(node))
+                .count(),
+            under_explored_queries: under_explored_queries.get(),
+            under_explored: under_explored_queries.get() * 2 > known_queries.get(),
+        };
+        (recommendations, stats)
+    }
+
+    /// Counts the nodes (other than `from`) within `max_hops` of `from`.
+    fn reachable_nodes(&self, from: &RecommenderNode<T>, max_hops: usize) -> usize {
+        self.graph
+            .neighbors_within(from, max_hops)
+            .len()
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_under_exploration() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..2000 {
+            recommender.tag_object(&obj, &format!("{}", obj % 10));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+        let queries = [RecommenderNode::Object(0), RecommenderNode::Object(1)];
+
+        let (recommendations, stats) =
+            recommender.recommendations_with_stats(&queries, 5, 50, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.is_empty());
+        assert!(stats.under_explored);
+        assert_eq!(stats.under_explored_queries, 2);
+        assert!(stats.steps <= 60);
+        assert!(stats.candidates > 0);
+
+        let mut small: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            small.tag_object(&obj, &format!("{}", obj % 2));
+        }
+        let (_, stats) =
+            small.recommendations_with_stats(&queries, 3, 10000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!stats.under_explored);
+        assert_eq!(stats.under_explored_queries, 0);
+        assert_eq!(small.reachable_nodes(&queries[0], 2), 10);
+    }
+}
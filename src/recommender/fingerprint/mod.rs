@@ -0,0 +1,210 @@
+//! # Fingerprint
+//!
+//! The `fingerprint` module is a collection of utilities to summarize the
+//! structure of a recommender, so that snapshots taken over time can be
+//! compared, e.g. to detect a bad ingestion.
+
+use std::hash::Hash;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Compact summary of the structure of a recommender.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Fingerprint {
+    /// Number of objects.
+    pub objects: usize,
+    /// Number of tags.
+    pub tags: usize,
+    /// Number of edges.
+    pub edges: usize,
+    /// Highest degree of a node.
+    pub max_degree: usize,
+    /// Number of nodes in each degree bucket. The first bucket counts the
+    /// nodes without edges and the bucket `i > 0` counts the nodes with a
+    /// degree in `[2^(i-1), 2^i)`.
+    pub degree_histogram: Vec<usize>,
+    /// Number of connected components of the graph.
+    pub components: usize,
+}
+
+/// Difference between two fingerprints, as the change of each value from
+/// the first fingerprint to the second one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FingerprintDiff {
+    /// Change in the number of objects.
+    pub objects: i64,
+    /// Change in the number of tags.
+    pub tags: i64,
+    /// Change in the number of edges.
+    pub edges: i64,
+    /// Change in the highest degree of a node.
+    pub max_degree: i64,
+    /// Change in the number of nodes of each degree bucket.
+    pub degree_histogram: Vec<i64>,
+    /// Change in the number of connected components.
+    pub components: i64,
+}
+
+fn delta(from: usize, to: usize) -> i64 {
+    to as i64 - from as i64
+}
+
+fn degree_bucket(degree: usize) -> usize {
+    (usize::BITS - degree.leading_zeros()) as usize
+}
+
+impl Fingerprint {
+    /// Computes the changes from this fingerprint to `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// let before = recommender.structural_fingerprint();
+    ///
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    /// let diff = before.diff(&recommender.structural_fingerprint());
+    ///
+    /// assert_eq!(diff.objects, 1);
+    /// assert_eq!(diff.edges, 1);
+    /// assert_eq!(diff.components, 0);
+    /// ```
+    pub fn diff(&self, other: &Fingerprint) -> FingerprintDiff {
+        let buckets = self
+            .degree_histogram
+            .len()
+            .max(other.degree_histogram.len());
+        let degree_histogram = (0..buckets)
+            .map(|i| {
+                delta(
+                    self.degree_histogram.get(i).cloned().unwrap_or(0),
+                    other.degree_histogram.get(i).cloned().unwrap_or(0),
+                )
+            })
+            .collect();
+        FingerprintDiff {
+            objects: delta(self.objects, other.objects),
+            tags: delta(self.tags, other.tags),
+            edges: delta(self.edges, other.edges),
+            max_degree: delta(self.max_degree, other.max_degree),
+            degree_histogram,
+            components: delta(self.components, other.components),
+        }
+    }
+}
+
+impl FingerprintDiff {
+    /// Checks if both fingerprints were the same.
+    pub fn is_unchanged(&self) -> bool {
+        self.objects == 0
+            && self.tags == 0
+            && self.edges == 0
+            && self.max_degree == 0
+            && self.degree_histogram.iter().all(|d| *d == 0)
+            && self.components == 0
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Summarizes the structure of the recommender with the number of
+    /// nodes and edges, an histogram of the degrees and the number of
+    /// connected components.
+    ///
+    /// Computing the components requires a search over the whole graph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    /// recommender.add_tag("Comedy");
+    ///
+    /// let fingerprint = recommender.structural_fingerprint();
+    /// assert_eq!(fingerprint.objects, 2);
+    /// assert_eq!(fingerprint.tags, 2);
+    /// assert_eq!(fingerprint.edges, 2);
+    /// assert_eq!(fingerprint.degree_histogram, vec![1, 2, 1]);
+    /// assert_eq!(fingerprint.components, 2);
+    /// ```
+    pub fn structural_fingerprint(&self) -> Fingerprint {
+        let mut objects = 0;
+        let mut tags = 0;
+        let mut degree_histogram: Vec<usize> = Vec::new();
+        for node in self.graph.nodes() {
+            let degree = self.graph.degree(node);
+            match node {
+                RecommenderNode::Object(_) => objects += 1,
+                RecommenderNode::Tag(_) => tags += 1,
+            }
+            let bucket = degree_bucket(degree);
+            if degree_histogram.len() <= bucket {
+                degree_histogram.resize(bucket + 1, 0);
+            }
+            degree_histogram[bucket] += 1;
+        }
+        Fingerprint {
+            objects,
+            tags,
+            edges: self.graph.edge_count(),
+            max_degree: self.graph.max_degree(),
+            degree_histogram,
+            components: self.graph.connected_components().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fingerprint_diff_after_large_edit() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..100 {
+            recommender.tag_object(&obj, &format!("{}", obj % 10));
+        }
+
+        let before = recommender.structural_fingerprint();
+        assert_eq!(before.objects, 100);
+        assert_eq!(before.tags, 10);
+        assert_eq!(before.edges, 100);
+        assert_eq!(before.max_degree, 10);
+        assert_eq!(before.degree_histogram, vec![0, 100, 0, 0, 10]);
+        assert_eq!(before.components, 10);
+        assert!(before.diff(&before).is_unchanged());
+        assert!(before
+            .diff(&recommender.structural_fingerprint())
+            .is_unchanged());
+
+        // A bad ingestion that links everything to a single tag and adds
+        // unconnected objects
+        for obj in 0..100 {
+            recommender.tag_object(&obj, "Everything");
+        }
+        for obj in 100..150 {
+            recommender.add_object(&obj);
+        }
+
+        let after = recommender.structural_fingerprint();
+        let diff = before.diff(&after);
+        assert!(!diff.is_unchanged());
+        assert_eq!(diff.objects, 50);
+        assert_eq!(diff.tags, 1);
+        assert_eq!(diff.edges, 100);
+        assert_eq!(diff.max_degree, 90);
+        assert_eq!(diff.degree_histogram, vec![50, -100, 100, 0, 0, 0, 0, 1]);
+        assert_eq!(diff.components, 50 + 1 - 10);
+
+        let reverse = after.diff(&before);
+        assert_eq!(reverse.objects, -50);
+        assert_eq!(reverse.degree_histogram[7], -1);
+    }
+}
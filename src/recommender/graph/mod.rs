@@ -9,18 +9,107 @@
 extern crate rand;
 use rand::rngs::OsRng;
 use rand::Rng;
+use rand::RngCore;
 
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::LinkedList;
+use std::collections::VecDeque;
 use std::fmt;
 use std::hash::Hash;
-use std::iter::FromIterator;
+use std::sync::Arc;
+
+type RngFactory = dyn Fn() -> Box<dyn RngCore> + Send + Sync;
+
+thread_local! {
+    // Reused by every sample, to avoid an allocation per hop
+    static CUMULATIVE_WEIGHTS: RefCell<Vec<f64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Converts the output of a weight function into a valid weight.
+///
+/// Negative, infinite and `NaN` weights are converted to `0.0`, so the
+/// respective edges are never traversed.
+///
+/// # Examples
+///
+/// ```
+/// use pixie_rust::recommender::graph::sanitize_weight;
+///
+/// assert_eq!(sanitize_weight(2.0), 2.0);
+/// assert_eq!(sanitize_weight(-2.0), 0.0);
+/// assert_eq!(sanitize_weight(f32::INFINITY), 0.0);
+/// assert_eq!(sanitize_weight(f32::NAN), 0.0);
+/// ```
+pub fn sanitize_weight(weight: f32) -> f32 {
+    if weight.is_finite() && weight > 0.0 {
+        weight
+    } else {
+        0.0
+    }
+}
+
+/// Picks one of `elems` at random, with a probability proportional to
+/// its (sanitized) weight, or `None` if every weight is 0.
+pub(crate) fn weighted_sample<T: Clone>(
+    rng: &mut (impl Rng + ?Sized),
+    elems: &[&T],
+    weight_fun: impl Fn(&T) -> f32,
+) -> Option<T> {
+    CUMULATIVE_WEIGHTS.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut cumulative_weights) => {
+            weighted_sample_with(rng, elems, weight_fun, &mut cumulative_weights)
+        }
+        // The weight function is sampling as well (e.g. it performs a walk)
+        Err(_) => weighted_sample_with(rng, elems, weight_fun, &mut Vec::new()),
+    })
+}
+
+fn weighted_sample_with<T: Clone>(
+    rng: &mut (impl Rng + ?Sized),
+    elems: &[&T],
+    weight_fun: impl Fn(&T) -> f32,
+    cumulative_weights: &mut Vec<f64>,
+) -> Option<T> {
+    cumulative_weights.clear();
+    let mut total_weight = 0.0;
+    for elem in elems {
+        total_weight += f64::from(sanitize_weight(weight_fun(elem)));
+        cumulative_weights.push(total_weight);
+    }
+    if total_weight == 0.0 {
+        return None;
+    }
+    let goal: f64 = rng.gen_range(0.0, total_weight);
+    // Elements with a weight of 0 never hold the first cumulative
+    // weight above the goal, so they are never picked
+    let index = cumulative_weights.partition_point(|weight| *weight <= goal);
+    elems.get(index).map(|elem| (*elem).clone())
+}
+
+/// Result of a random walk.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WalkResult<T> {
+    /// Visited nodes, in reverse order.
+    pub nodes: LinkedList<T>,
+    /// Whether the walk stopped before reaching the maximum number of
+    /// hops, e.g. due to a dead end.
+    pub terminated_early: bool,
+}
 
 /// Data structure containing an undirected graph.
 pub struct Graph<T> {
     data: HashMap<T, HashSet<T>>,
+    // Only the weights different from 1.0 are stored, in both directions
+    edge_weights: HashMap<T, HashMap<T, f32>>,
     max_degree: usize,
+    edge_count: usize,
+    self_loop_count: usize,
+    batching: bool,
+    rng_factory: Option<Arc<RngFactory>>,
 }
 
 impl<T: Eq + Clone + Hash> Graph<T> {
@@ -28,22 +117,154 @@ impl<T: Eq + Clone + Hash> Graph<T> {
     pub fn new() -> Graph<T> {
         Graph {
             data: HashMap::new(),
+            edge_weights: HashMap::new(),
             max_degree: 0,
+            edge_count: 0,
+            self_loop_count: 0,
+            batching: false,
+            rng_factory: None,
+        }
+    }
+
+    /// Creates an empty graph that uses `rng_factory` to create the random
+    /// number generator of each walk, instead of the OS RNG.
+    ///
+    /// This can be used to share a thread local generator (avoiding a
+    /// system call per walk) or to make the walks reproducible with a
+    /// seeded generator. Note that the walks are only reproducible on the
+    /// same graph instance, as the iteration order of the neighbors is not
+    /// guaranteed to be the same across graphs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    /// use rand::rngs::StdRng;
+    /// use rand::RngCore;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut graph: Graph<u32> =
+    ///     Graph::with_rng_factory(|| Box::new(StdRng::seed_from_u64(42)) as Box<dyn RngCore>);
+    /// for i in 1..10 {
+    ///     graph.add_edge(&0, &i);
+    /// }
+    ///
+    /// let walk = graph.random_walk(&0, 10, |_, _| 1.0);
+    /// assert_eq!(graph.random_walk(&0, 10, |_, _| 1.0), walk);
+    /// ```
+    pub fn with_rng_factory(
+        rng_factory: impl Fn() -> Box<dyn RngCore> + Send + Sync + 'static,
+    ) -> Graph<T> {
+        Graph {
+            rng_factory: Some(Arc::new(rng_factory)),
+            ..Graph::new()
         }
     }
 
     /// Adds a node to the graph.
     pub fn add_node(&mut self, node: &T) {
-        self.data.entry(node.clone()).or_insert(HashSet::new());
+        self.data.entry(node.clone()).or_default();
     }
 
-    /// Adds an edge to the graph. The nodes are created, if needed.
+    /// Adds an edge to the graph, with a weight of `1.0`. The nodes are
+    /// created, if needed.
     pub fn add_edge(&mut self, node_a: &T, node_b: &T) {
+        self.add_edge_degrees(node_a, node_b);
+    }
+
+    /// Adds an edge with a stored weight to the graph. The nodes are
+    /// created, if needed.
+    ///
+    /// If the edge already exists, its weight is replaced. The weight can
+    /// be read with [`edge_weight`](#method.edge_weight), e.g. from the
+    /// weight function of a walk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_weighted_edge(&1, &2, 5.0);
+    /// graph.add_edge(&1, &3);
+    /// assert_eq!(graph.edge_weight(&2, &1), Some(5.0));
+    /// assert_eq!(graph.edge_weight(&1, &3), Some(1.0));
+    /// assert_eq!(graph.edge_weight(&2, &3), None);
+    ///
+    /// let visited = graph.random_walk(&1, 10, |from, to| {
+    ///     graph.edge_weight(from, to).unwrap_or(0.0) * (*to as f32)
+    /// });
+    /// assert_eq!(visited.len(), 10);
+    /// ```
+    pub fn add_weighted_edge(&mut self, node_a: &T, node_b: &T, weight: f32) {
+        self.add_edge_degrees(node_a, node_b);
+        self.set_edge_weight(node_a, node_b, weight);
+    }
+
+    /// Returns the stored weight of the edge between two nodes, or `None`
+    /// if there's no such edge.
+    pub fn edge_weight(&self, node_a: &T, node_b: &T) -> Option<f32> {
+        if !self.data.get(node_a)?.contains(node_b) {
+            return None;
+        }
+        let stored = self
+            .edge_weights
+            .get(node_a)
+            .and_then(|weights| weights.get(node_b));
+        Some(stored.cloned().unwrap_or(1.0))
+    }
+
+    fn set_edge_weight(&mut self, node_a: &T, node_b: &T, weight: f32) {
+        if weight == 1.0 {
+            self.remove_edge_weight(node_a, node_b);
+        } else {
+            self.edge_weights
+                .entry(node_a.clone())
+                .or_default()
+                .insert(node_b.clone(), weight);
+            self.edge_weights
+                .entry(node_b.clone())
+                .or_default()
+                .insert(node_a.clone(), weight);
+        }
+    }
+
+    fn remove_edge_weight(&mut self, node_a: &T, node_b: &T) {
+        if self.edge_weights.is_empty() {
+            return;
+        }
+        for (from, to) in [(node_a, node_b), (node_b, node_a)].iter() {
+            if let Some(weights) = self.edge_weights.get_mut(*from) {
+                weights.remove(*to);
+                if weights.is_empty() {
+                    self.edge_weights.remove(*from);
+                }
+            }
+        }
+    }
+
+    /// Same as [`add_edge`](#method.add_edge), but returns the degrees of
+    /// both nodes after the insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// assert_eq!(graph.add_edge_degrees(&1, &3), (2, 1));
+    /// assert_eq!(graph.add_edge_degrees(&1, &3), (2, 1));
+    /// ```
+    pub fn add_edge_degrees(&mut self, node_a: &T, node_b: &T) -> (usize, usize) {
+        let mut inserted = true;
         let degree_a = self
             .data
             .entry(node_a.clone())
             .and_modify(|e| {
-                e.insert(node_b.clone());
+                inserted = e.insert(node_b.clone());
             })
             .or_insert({
                 let mut h = HashSet::new();
@@ -63,16 +284,219 @@ impl<T: Eq + Clone + Hash> Graph<T> {
                 h
             })
             .len();
+        self.remove_edge_weight(node_a, node_b);
+
+        if inserted {
+            self.edge_count += 1;
+            if node_a == node_b {
+                self.self_loop_count += 1;
+            }
+        }
+
+        if !self.batching {
+            self.max_degree = self.max_degree.max(degree_a).max(degree_b);
+        }
+
+        (degree_a, degree_b)
+    }
+
+    /// Removes the edge between two nodes, returning whether the edge
+    /// existed. The nodes are kept, even if they become isolated.
+    ///
+    /// If one of the nodes held the maximum degree, the maximum degree is
+    /// recomputed (unless a batch is in progress).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// assert_eq!(graph.max_degree(), 2);
+    ///
+    /// assert!(graph.remove_edge(&1, &2));
+    /// assert!(!graph.remove_edge(&1, &2));
+    /// assert_eq!(graph.max_degree(), 1);
+    /// assert!(graph.contains(&2));
+    /// ```
+    pub fn remove_edge(&mut self, node_a: &T, node_b: &T) -> bool {
+        let removed = self
+            .data
+            .get_mut(node_a)
+            .map(|succs| succs.remove(node_b))
+            .unwrap_or(false);
+        if !removed {
+            return false;
+        }
+
+        self.edge_count -= 1;
+        if node_a == node_b {
+            self.self_loop_count -= 1;
+        }
+        self.remove_edge_weight(node_a, node_b);
+        let degree_b = self
+            .data
+            .get_mut(node_b)
+            .map(|succs| {
+                succs.remove(node_a);
+                succs.len()
+            })
+            .unwrap_or(0);
+        let degree_a = self.degree(node_a);
+
+        let held_max_degree = degree_a + 1 == self.max_degree || degree_b + 1 == self.max_degree;
+        if held_max_degree && !self.batching {
+            self.max_degree = self.compute_max_degree();
+        }
+        true
+    }
+
+    /// Removes a node and all its edges, returning whether the node
+    /// existed.
+    ///
+    /// If the node or one of its neighbors held the maximum degree, the
+    /// maximum degree is recomputed (unless a batch is in progress).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// graph.add_edge(&4, &3);
+    /// assert_eq!(graph.max_degree(), 2);
+    ///
+    /// assert!(graph.remove_node(&1));
+    /// assert!(!graph.remove_node(&1));
+    /// assert!(!graph.contains(&1));
+    /// assert_eq!(graph.degree(&3), 1);
+    /// assert_eq!(graph.max_degree(), 1);
+    /// ```
+    pub fn remove_node(&mut self, node: &T) -> bool {
+        let succs = match self.data.remove(node) {
+            Some(succs) => succs,
+            None => return false,
+        };
+
+        let mut held_max_degree = succs.len() == self.max_degree;
+        for succ in succs.iter() {
+            self.edge_count -= 1;
+            if succ == node {
+                self.self_loop_count -= 1;
+            } else if let Some(succ_succs) = self.data.get_mut(succ) {
+                held_max_degree |= succ_succs.len() == self.max_degree;
+                succ_succs.remove(node);
+            }
+            self.remove_edge_weight(node, succ);
+        }
 
-        if degree_a > self.max_degree {
-            self.max_degree = degree_a;
+        if held_max_degree && !self.batching {
+            self.max_degree = self.compute_max_degree();
         }
+        true
+    }
+
+    /// Starts a batch of edits.
+    ///
+    /// During a batch, the cached maximum degree is not updated on every
+    /// edit, which makes bulk insertions cheaper. It is recomputed once the
+    /// batch ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.begin_batch();
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// graph.end_batch();
+    /// assert_eq!(graph.max_degree(), 2);
+    /// ```
+    pub fn begin_batch(&mut self) {
+        self.batching = true;
+    }
+
+    /// Ends a batch of edits, updating the cached information about the
+    /// graph.
+    pub fn end_batch(&mut self) {
+        self.batching = false;
+        self.rebuild_index();
+    }
 
-        if degree_b > self.max_degree {
-            self.max_degree = degree_b;
+    /// Returns a handle to a node, so that multiple operations can be
+    /// performed on it. The node is created, if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.node_entry(&1).add_edge(&2).add_edge(&3);
+    /// assert_eq!(graph.degree(&1), 2);
+    /// assert_eq!(graph.degree(&2), 1);
+    /// ```
+    pub fn node_entry(&mut self, node: &T) -> NodeEntry<'_, T> {
+        self.add_node(node);
+        NodeEntry {
+            graph: self,
+            node: node.clone(),
         }
     }
 
+    /// Checks if a node is part of the graph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_node(&1);
+    /// assert!(graph.contains(&1));
+    /// assert!(!graph.contains(&2));
+    /// ```
+    pub fn contains(&self, node: &T) -> bool {
+        self.data.contains_key(node)
+    }
+
+    /// Returns a reference to the node stored in the graph that is equal
+    /// to `node`, if it exists.
+    pub fn stored_node(&self, node: &T) -> Option<&T> {
+        self.data.get_key_value(node).map(|(stored, _)| stored)
+    }
+
+    /// Lists all nodes of the graph, in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_node(&1);
+    /// graph.add_edge(&2, &3);
+    ///
+    /// let nodes = graph.nodes().cloned().collect::<HashSet<u32>>();
+    /// assert_eq!(nodes, [1, 2, 3].iter().cloned().collect());
+    /// ```
+    pub fn nodes(&self) -> impl Iterator<Item = &T> {
+        self.data.keys()
+    }
+
     /// Lists the successors of a node.
     ///
     /// # Examples
@@ -120,10 +544,14 @@ impl<T: Eq + Clone + Hash> Graph<T> {
     /// assert_eq!(graph.max_degree(), 2);
     /// ```
     pub fn max_degree(&self) -> usize {
-        self.max_degree
+        if self.batching {
+            self.compute_max_degree()
+        } else {
+            self.max_degree
+        }
     }
 
-    /// Returns the degree of a node.
+    /// Returns the number of nodes of the graph.
     ///
     /// # Examples
     ///
@@ -132,60 +560,44 @@ impl<T: Eq + Clone + Hash> Graph<T> {
     ///
     /// let mut graph: Graph<u32> = Graph::new();
     ///
-    /// graph.add_node(&1);
-    /// graph.add_node(&2);
-    /// graph.add_node(&3);
-    /// assert_eq!(graph.degree(&1), 0);
     /// graph.add_edge(&1, &2);
-    /// assert_eq!(graph.degree(&1), 1);
-    /// graph.add_edge(&1, &3);
-    /// assert_eq!(graph.degree(&1), 2);
-    /// assert_eq!(graph.degree(&2), 1);
-    /// assert_eq!(graph.degree(&3), 1);
+    /// graph.add_node(&3);
+    /// assert_eq!(graph.node_count(), 3);
     /// ```
-    pub fn degree(&self, node: &T) -> usize {
-        self.data.get(node).map(|x| x.len()).unwrap_or(0)
+    pub fn node_count(&self) -> usize {
+        self.data.len()
     }
 
-    fn weighted_sample(
-        rng: &mut impl Rng,
-        elems: LinkedList<&T>,
-        weight_fun: impl Fn(&T) -> f32,
-    ) -> Option<T> {
-        let safe_weight_fun = |x| {
-            let unsafe_weight = weight_fun(x);
-            let clamped_weight = unsafe_weight.max(0.0);
-            if clamped_weight.is_infinite() {
-                0.0
-            } else {
-                clamped_weight
-            }
-        };
-        let total_weight: f32 = elems.iter().map(|e| safe_weight_fun(e)).sum();
-        if total_weight == 0.0 {
-            None
-        } else {
-            let mut goal: f32 = rng.gen_range(0.0, total_weight);
-            let mut iterator = elems.iter();
-            let mut choice: Option<&&T> = iterator.next();
-            while choice.is_some() {
-                let value = choice.unwrap();
-                goal = goal - safe_weight_fun(value);
-                if goal <= 0.0 {
-                    break;
-                } else {
-                    choice = iterator.next();
-                }
-            }
-            choice.cloned().cloned()
-        }
+    /// Returns the number of undirected edges of the graph.
+    ///
+    /// A self-loop (an edge from a node to itself) counts as a single
+    /// edge, and it also adds only `1` to the degree of its node.
+    ///
+    /// The number of edges is kept up to date on every edit, so this runs
+    /// in constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &1);
+    /// graph.add_edge(&1, &3);
+    /// graph.add_edge(&3, &3);
+    /// assert_eq!(graph.edge_count(), 3);
+    /// ```
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
     }
 
-    /// Performs a random walk on a graph.
-    /// It picks the next node according to a weight function
-    /// `(from, to) = weight`.
+    /// Returns the average degree of the nodes of the graph, or `0.0` if
+    /// the graph is empty.
     ///
-    /// It returns the list of visited nodes in reverse order.
+    /// The number of edges is kept up to date on every edit, so this runs
+    /// in constant time.
     ///
     /// # Examples
     ///
@@ -194,68 +606,1017 @@ impl<T: Eq + Clone + Hash> Graph<T> {
     ///
     /// let mut graph: Graph<u32> = Graph::new();
     ///
-    /// graph.add_node(&1);
-    /// graph.add_node(&2);
-    /// graph.add_node(&3);
+    /// assert_eq!(graph.average_degree(), 0.0);
     /// graph.add_edge(&1, &2);
     /// graph.add_edge(&1, &3);
-    /// let visited = graph.random_walk(&1, 200, |_, x| x.clone() as f32);
-    /// assert_eq!(visited.len(), 200);
-    ///
-    /// // The node 3 should be visited more often due to the weight function
-    /// assert!(
-    ///     visited.iter().filter(|&&x| x == 2).count() <
-    ///     visited.iter().filter(|&&x| x == 3).count()
-    /// );
+    /// graph.add_node(&4);
+    /// assert_eq!(graph.average_degree(), 1.0);
     /// ```
-    pub fn random_walk(
-        &self,
-        starting_node: &T,
-        max_hops: u8,
-        weight_fun: impl Fn(&T, &T) -> f32,
-    ) -> LinkedList<T> {
-        let mut rng = OsRng::new().expect("Failed to create the RNG");
-        let mut visited: LinkedList<T> = LinkedList::new();
-        if self.data.contains_key(starting_node) {
-            let mut current_node = starting_node.clone();
-            let mut hops = max_hops;
-            while hops > 0 {
-                hops = hops - 1;
-                visited.push_front(current_node.clone());
-                let succs = self.successors(&current_node);
-                let next = Graph::weighted_sample(
-                    &mut rng,
-                    LinkedList::from_iter(succs.iter()),
-                    |next_node| weight_fun(&current_node, next_node),
-                );
-                match next {
-                    None => break,
-                    Some(v) => current_node = v.clone(),
-                };
-            }
+    pub fn average_degree(&self) -> f64 {
+        if self.data.is_empty() {
+            0.0
+        } else {
+            // Self loops only add one neighbor to their node
+            let degree_sum = 2 * self.edge_count - self.self_loop_count;
+            degree_sum as f64 / self.data.len() as f64
         }
-        visited
     }
-}
 
-impl<T: fmt::Debug + Eq + Hash> fmt::Debug for Graph<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Graph {:?} / Max Degree = {:?}",
-            self.data, self.max_degree
-        )
+    fn compute_max_degree(&self) -> usize {
+        self.data
+            .values()
+            .map(|succs| succs.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Splits the graph into its connected components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_node(&1);
+    /// graph.add_edge(&2, &3);
+    /// graph.add_edge(&3, &4);
+    ///
+    /// let mut component_sizes = graph
+    ///     .connected_components()
+    ///     .iter()
+    ///     .map(|component| component.len())
+    ///     .collect::<Vec<usize>>();
+    /// component_sizes.sort();
+    /// assert_eq!(component_sizes, vec![1, 3]);
+    /// ```
+    pub fn connected_components(&self) -> Vec<HashSet<T>> {
+        let mut components: Vec<HashSet<T>> = Vec::new();
+        let mut visited: HashSet<&T> = HashSet::new();
+        for node in self.data.keys() {
+            if !visited.insert(node) {
+                continue;
+            }
+            let mut component: HashSet<T> = HashSet::new();
+            let mut pending: Vec<&T> = vec![node];
+            while let Some(current) = pending.pop() {
+                component.insert(current.clone());
+                for succ in self.data.get(current).into_iter().flatten() {
+                    if visited.insert(succ) {
+                        pending.push(succ);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Checks if the nodes of the graph can be split in two sets, such that
+    /// every edge connects nodes of different sets (i.e. there is no cycle
+    /// with an odd length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// assert!(graph.is_bipartite());
+    ///
+    /// graph.add_edge(&3, &1);
+    /// assert!(!graph.is_bipartite());
+    /// ```
+    pub fn is_bipartite(&self) -> bool {
+        let mut colors: HashMap<&T, bool> = HashMap::new();
+        for node in self.data.keys() {
+            if colors.contains_key(node) {
+                continue;
+            }
+            colors.insert(node, false);
+            let mut pending: VecDeque<&T> = VecDeque::new();
+            pending.push_back(node);
+            while let Some(current) = pending.pop_front() {
+                let color = colors[current];
+                for succ in self.data.get(current).into_iter().flatten() {
+                    match colors.get(succ) {
+                        Some(succ_color) if *succ_color == color => return false,
+                        Some(_) => {}
+                        None => {
+                            colors.insert(succ, !color);
+                            pending.push_back(succ);
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Lists the nodes reachable from a node within `k` hops, including
+    /// the node itself, so with `k == 0` only the node is returned.
+    ///
+    /// If the node is not part of the graph, the set is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// graph.add_edge(&3, &4);
+    /// assert_eq!(graph.neighbors_within(&1, 0), vec![1].into_iter().collect());
+    /// assert_eq!(graph.neighbors_within(&2, 1), vec![1, 2, 3].into_iter().collect());
+    /// assert_eq!(graph.neighbors_within(&5, 1), HashSet::new());
+    /// ```
+    pub fn neighbors_within(&self, node: &T, k: usize) -> HashSet<T> {
+        let mut visited: HashSet<T> = HashSet::new();
+        if !self.data.contains_key(node) {
+            return visited;
+        }
+        visited.insert(node.clone());
+        let mut frontier = vec![node.clone()];
+        for _ in 0..k {
+            frontier = frontier
+                .iter()
+                .flat_map(|current| self.data.get(current).into_iter().flatten())
+                .filter(|succ| visited.insert((*succ).clone()))
+                .cloned()
+                .collect();
+            if frontier.is_empty() {
+                break;
+            }
+        }
+        visited
+    }
+
+    /// Returns the largest distance (in hops) from a node to any other
+    /// node of its connected component, or `None` if the node is not part
+    /// of the graph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// assert_eq!(graph.eccentricity(&1), Some(2));
+    /// assert_eq!(graph.eccentricity(&2), Some(1));
+    /// assert_eq!(graph.eccentricity(&4), None);
+    /// ```
+    pub fn eccentricity(&self, node: &T) -> Option<usize> {
+        self.data
+            .get_key_value(node)
+            .map(|(node, _)| self.farthest_node(node).1)
+    }
+
+    /// Returns the largest distance (in hops) between two nodes of the same
+    /// connected component, or `None` if the graph is empty.
+    ///
+    /// On graphs with up to 1000 nodes, the exact diameter is computed.
+    /// On larger graphs, the diameter is estimated with a few double sweeps
+    /// (a search from the farthest node of a sampled node), which is much
+    /// faster, but can underestimate the real diameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// assert_eq!(graph.diameter(), None);
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// graph.add_edge(&3, &4);
+    /// assert_eq!(graph.diameter(), Some(3));
+    /// ```
+    pub fn diameter(&self) -> Option<usize> {
+        const EXACT_DIAMETER_NODES: usize = 1000;
+        const DIAMETER_SWEEPS: usize = 16;
+        if self.data.len() <= EXACT_DIAMETER_NODES {
+            self.data
+                .keys()
+                .map(|node| self.farthest_node(node).1)
+                .max()
+        } else {
+            self.data
+                .keys()
+                .take(DIAMETER_SWEEPS)
+                .map(|node| {
+                    let (farthest, _) = self.farthest_node(node);
+                    self.farthest_node(farthest).1
+                })
+                .max()
+        }
+    }
+
+    /// Breadth-first search that returns the farthest node from `node` and
+    /// its distance.
+    fn farthest_node<'b>(&'b self, node: &'b T) -> (&'b T, usize) {
+        let mut distances: HashMap<&T, usize> = HashMap::new();
+        let mut pending: VecDeque<&T> = VecDeque::new();
+        let mut farthest = (node, 0);
+        distances.insert(node, 0);
+        pending.push_back(node);
+        while let Some(current) = pending.pop_front() {
+            let distance = distances[current];
+            if distance > farthest.1 {
+                farthest = (current, distance);
+            }
+            for succ in self.data.get(current).into_iter().flatten() {
+                if !distances.contains_key(succ) {
+                    distances.insert(succ, distance + 1);
+                    pending.push_back(succ);
+                }
+            }
+        }
+        farthest
+    }
+
+    /// Removes all nodes without any edge, returning how many nodes were
+    /// removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_node(&3);
+    ///
+    /// assert_eq!(graph.remove_isolated(), 1);
+    /// assert!(!graph.contains(&3));
+    /// ```
+    pub fn remove_isolated(&mut self) -> usize {
+        let nodes = self.data.len();
+        self.data.retain(|_, succs| !succs.is_empty());
+        nodes - self.data.len()
+    }
+
+    /// Returns the number of nodes that the graph can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Shrinks the memory used by the graph as much as possible.
+    ///
+    /// This is useful after removing a large number of nodes or edges.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        for succs in self.data.values_mut() {
+            succs.shrink_to_fit();
+        }
+        self.edge_weights.shrink_to_fit();
+    }
+
+    /// Recomputes all cached information about the graph (e.g. the
+    /// maximum degree) from scratch.
+    pub fn rebuild_index(&mut self) {
+        self.max_degree = self.compute_max_degree();
+        let degree_sum: usize = self.data.values().map(|succs| succs.len()).sum();
+        self.self_loop_count = self
+            .data
+            .iter()
+            .filter(|(node, succs)| succs.contains(node))
+            .count();
+        self.edge_count = (degree_sum + self.self_loop_count) / 2;
+    }
+
+    /// Returns the degree of a node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_node(&1);
+    /// graph.add_node(&2);
+    /// graph.add_node(&3);
+    /// assert_eq!(graph.degree(&1), 0);
+    /// graph.add_edge(&1, &2);
+    /// assert_eq!(graph.degree(&1), 1);
+    /// graph.add_edge(&1, &3);
+    /// assert_eq!(graph.degree(&1), 2);
+    /// assert_eq!(graph.degree(&2), 1);
+    /// assert_eq!(graph.degree(&3), 1);
+    /// ```
+    pub fn degree(&self, node: &T) -> usize {
+        self.data.get(node).map(|x| x.len()).unwrap_or(0)
+    }
+
+    /// Lists the edges of a node, as pairs of `(neighbor, weight)`, in no
+    /// particular order.
+    ///
+    /// Edges added without a weight have a weight of `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_weighted_edge(&3, &2, 0.5);
+    /// assert_eq!(graph.edges_of(&1), vec![(2, 1.0)]);
+    /// assert_eq!(graph.edges_of(&3), vec![(2, 0.5)]);
+    /// assert_eq!(graph.edges_of(&4), vec![]);
+    /// ```
+    pub fn edges_of(&self, node: &T) -> Vec<(T, f32)> {
+        let weights = self.edge_weights.get(node);
+        self.data
+            .get(node)
+            .map(|succs| {
+                succs
+                    .iter()
+                    .map(|succ| {
+                        let weight = weights.and_then(|w| w.get(succ)).cloned();
+                        (succ.clone(), weight.unwrap_or(1.0))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Lists the successors of a node, paired with their weight according
+    /// to a weight function `(from, to) = weight`.
+    ///
+    /// The result is sorted by descending weight. The order of neighbors
+    /// with the same weight is unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// assert_eq!(
+    ///     graph.neighbors_sorted(&1, |_, x| *x as f32),
+    ///     vec![(3, 3.0), (2, 2.0)]
+    /// );
+    /// ```
+    pub fn neighbors_sorted(&self, node: &T, weight_fun: impl Fn(&T, &T) -> f32) -> Vec<(T, f32)> {
+        let mut neighbors = self
+            .data
+            .get(node)
+            .map(|succs| {
+                succs
+                    .iter()
+                    .map(|succ| (succ.clone(), weight_fun(node, succ)))
+                    .collect::<Vec<(T, f32)>>()
+            })
+            .unwrap_or_default();
+        neighbors.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        neighbors
+    }
+
+    fn weighted_sample_u64(
+        rng: &mut (impl Rng + ?Sized),
+        elems: &[&T],
+        weight_fun: impl Fn(&T) -> u64,
+    ) -> Option<T> {
+        let weights = elems.iter().map(|e| weight_fun(e)).collect::<Vec<u64>>();
+        let total_weight: u128 = weights.iter().map(|w| u128::from(*w)).sum();
+        if total_weight == 0 {
+            None
+        } else {
+            let mut goal: u128 = rng.gen_range(0, total_weight);
+            for (elem, weight) in elems.iter().zip(weights.iter()) {
+                let weight = u128::from(*weight);
+                if goal < weight {
+                    return Some((*elem).clone());
+                }
+                goal -= weight;
+            }
+            None
+        }
+    }
+
+    /// Performs a random walk on a graph.
+    /// It picks the next node according to a weight function
+    /// `(from, to) = weight`, which can read the stored weight of the edge
+    /// with [`edge_weight`](#method.edge_weight).
+    ///
+    /// It returns the list of visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_node(&1);
+    /// graph.add_node(&2);
+    /// graph.add_node(&3);
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let visited = graph.random_walk(&1, 200, |_, x| x.clone() as f32);
+    /// assert_eq!(visited.len(), 200);
+    ///
+    /// // The node 3 should be visited more often due to the weight function
+    /// assert!(
+    ///     visited.iter().filter(|&&x| x == 2).count() <
+    ///     visited.iter().filter(|&&x| x == 3).count()
+    /// );
+    /// ```
+    pub fn random_walk(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> LinkedList<T> {
+        self.random_walk_with_rng(starting_node, max_hops, weight_fun, &mut self.new_rng())
+    }
+
+    /// Performs `count` random walks from the same node, as in
+    /// [`random_walk`](#method.random_walk).
+    ///
+    /// All walks share the same random number generator, which is cheaper
+    /// than creating a new one for each walk. Each walk contains the
+    /// visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let walks = graph.random_walks(&1, 5, 3, |_, _| 1.0);
+    /// assert_eq!(walks.len(), 5);
+    /// assert!(walks.iter().all(|walk| walk.len() == 3 && walk[0] == 1 && walk[2] == 1));
+    /// ```
+    pub fn random_walks(
+        &self,
+        starting_node: &T,
+        count: usize,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> Vec<Vec<T>> {
+        let mut rng = self.new_rng();
+        (0..count)
+            .map(|_| {
+                self.random_walk_with_rng(starting_node, max_hops, &weight_fun, &mut rng)
+                    .into_iter()
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Same as [`random_walk`](#method.random_walk), but also reports if
+    /// the walk terminated early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_node(&3);
+    ///
+    /// let result = graph.random_walk_result(&1, 10, |_, _| 1.0);
+    /// assert_eq!(result.nodes.len(), 10);
+    /// assert!(!result.terminated_early);
+    ///
+    /// let result = graph.random_walk_result(&3, 10, |_, _| 1.0);
+    /// assert_eq!(result.nodes.into_iter().collect::<Vec<u32>>(), vec![3]);
+    /// assert!(result.terminated_early);
+    /// ```
+    pub fn random_walk_result(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> WalkResult<T> {
+        self.walk_result_with_rng(
+            self.new_rng().as_mut(),
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| {
+                weighted_sample(rng, candidates, |next_node| {
+                    weight_fun(current_node, next_node)
+                })
+            },
+        )
+    }
+
+    /// Performs a random walk on a graph, picking the next node according
+    /// to the weights stored in the graph (see [`edges_of`]), without any
+    /// weight function.
+    ///
+    /// Other than that, it behaves like [`random_walk`](#method.random_walk).
+    ///
+    /// [`edges_of`]: #method.edges_of
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// let visited = graph.random_walk_stored(&1, 10);
+    /// assert_eq!(visited.len(), 10);
+    ///
+    /// graph.add_weighted_edge(&2, &3, 0.0);
+    /// let visited = graph.random_walk_stored(&3, 10);
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![3]);
+    /// ```
+    pub fn random_walk_stored(&self, starting_node: &T, max_hops: u8) -> LinkedList<T> {
+        self.walk_with(
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| match self.edge_weights.get(current_node) {
+                Some(weights) => weighted_sample(rng, candidates, |next_node| {
+                    weights.get(next_node).cloned().unwrap_or(1.0)
+                }),
+                None if candidates.is_empty() => None,
+                None => {
+                    let index = rng.gen_range(0, candidates.len());
+                    Some(candidates[index].clone())
+                }
+            },
+        )
+    }
+
+    /// Performs a random walk on a graph that never goes back to the
+    /// previous node, unless it's the only option.
+    ///
+    /// Other than that, it behaves like [`random_walk`](#method.random_walk),
+    /// and also returns the list of visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&2, &3);
+    /// let visited = graph.non_backtracking_random_walk(&1, 4, |_, _| 1.0);
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![2, 3, 2, 1]);
+    /// ```
+    pub fn non_backtracking_random_walk(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> LinkedList<T> {
+        self.walk(starting_node, max_hops, weight_fun, true)
+    }
+
+    /// Performs a random walk on a graph, where the weight function
+    /// `(from, to) = weight` returns integer weights.
+    ///
+    /// This behaves like [`random_walk`](#method.random_walk), but the
+    /// weights are never converted to floating point numbers, so large
+    /// weights (e.g. counts above 2^24) don't lose precision.
+    ///
+    /// It returns the list of visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let visited = graph.random_walk_u64(&1, 4, |_, x| if *x == 2 { 0 } else { 1 << 40 });
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![3, 1, 3, 1]);
+    /// ```
+    pub fn random_walk_u64(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> u64,
+    ) -> LinkedList<T> {
+        self.walk_with(
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| {
+                Graph::weighted_sample_u64(rng, candidates, |next_node| {
+                    weight_fun(current_node, next_node)
+                })
+            },
+        )
+    }
+
+    /// Performs a random walk on a graph, where the weight function
+    /// `(from, to) = weight` can exclude edges by returning `None`.
+    ///
+    /// Excluded edges are never traversed, and are not considered when
+    /// computing the total weight of the candidates.
+    ///
+    /// It returns the list of visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let visited = graph.random_walk_filtered(&1, 4, |_, x| if *x == 2 { None } else { Some(1.0) });
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![3, 1, 3, 1]);
+    /// ```
+    pub fn random_walk_filtered(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> Option<f32>,
+    ) -> LinkedList<T> {
+        self.walk_with(
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| {
+                let allowed = candidates
+                    .iter()
+                    .filter(|next_node| weight_fun(current_node, next_node).is_some())
+                    .cloned()
+                    .collect::<Vec<&T>>();
+                weighted_sample(rng, &allowed, |next_node| {
+                    weight_fun(current_node, next_node).unwrap_or(0.0)
+                })
+            },
+        )
+    }
+
+    /// Same as [`random_walk`](#method.random_walk), but the walk uses the
+    /// given random number generator.
+    ///
+    /// With a seeded generator, the walks are reproducible (on the same
+    /// graph instance). Reusing the same generator for many walks is also
+    /// cheaper than creating a new one for each walk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    /// for i in 1..10 {
+    ///     graph.add_edge(&0, &i);
+    /// }
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let walk = graph.random_walk_with_rng(&0, 10, |_, _| 1.0, &mut rng);
+    /// assert_eq!(walk.len(), 10);
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// assert_eq!(graph.random_walk_with_rng(&0, 10, |_, _| 1.0, &mut rng), walk);
+    /// ```
+    pub fn random_walk_with_rng(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+        rng: &mut impl Rng,
+    ) -> LinkedList<T> {
+        self.walk_with_rng(
+            rng,
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| {
+                weighted_sample(rng, candidates, |next_node| {
+                    weight_fun(current_node, next_node)
+                })
+            },
+        )
+    }
+
+    fn walk(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+        no_backtrack: bool,
+    ) -> LinkedList<T> {
+        self.walk_with(
+            starting_node,
+            max_hops,
+            no_backtrack,
+            |rng, candidates, current_node| {
+                weighted_sample(rng, candidates, |next_node| {
+                    weight_fun(current_node, next_node)
+                })
+            },
+        )
+    }
+
+    fn walk_with(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        no_backtrack: bool,
+        sample: impl Fn(&mut dyn RngCore, &[&T], &T) -> Option<T>,
+    ) -> LinkedList<T> {
+        self.walk_with_rng(
+            self.new_rng().as_mut(),
+            starting_node,
+            max_hops,
+            no_backtrack,
+            sample,
+        )
+    }
+
+    /// Creates the random number generator of a walk.
+    pub(crate) fn new_rng(&self) -> Box<dyn RngCore> {
+        match &self.rng_factory {
+            Some(rng_factory) => rng_factory(),
+            None => Box::new(OsRng::new().expect("Failed to create the RNG")),
+        }
+    }
+
+    fn walk_with_rng(
+        &self,
+        rng: &mut dyn RngCore,
+        starting_node: &T,
+        max_hops: u8,
+        no_backtrack: bool,
+        sample: impl Fn(&mut dyn RngCore, &[&T], &T) -> Option<T>,
+    ) -> LinkedList<T> {
+        self.walk_result_with_rng(rng, starting_node, max_hops, no_backtrack, sample)
+            .nodes
+    }
+
+    fn walk_result_with_rng(
+        &self,
+        rng: &mut dyn RngCore,
+        starting_node: &T,
+        max_hops: u8,
+        no_backtrack: bool,
+        sample: impl Fn(&mut dyn RngCore, &[&T], &T) -> Option<T>,
+    ) -> WalkResult<T> {
+        let mut visited: LinkedList<T> = LinkedList::new();
+        let mut terminated_early = max_hops > 0;
+        if self.data.contains_key(starting_node) {
+            terminated_early = false;
+            let mut current_node = starting_node.clone();
+            let mut previous_node: Option<T> = None;
+            let mut hops = max_hops;
+            let mut candidates: Vec<&T> = Vec::new();
+            while hops > 0 {
+                hops -= 1;
+                visited.push_front(current_node.clone());
+                candidates.clear();
+                if let Some(succs) = self.data.get(&current_node) {
+                    candidates.extend(succs.iter().filter(|succ| {
+                        !no_backtrack || succs.len() == 1 || Some(*succ) != previous_node.as_ref()
+                    }));
+                }
+                match sample(rng, &candidates, &current_node) {
+                    None => {
+                        terminated_early = hops > 0;
+                        break;
+                    }
+                    Some(v) => previous_node = Some(std::mem::replace(&mut current_node, v)),
+                };
+            }
+        }
+        WalkResult {
+            nodes: visited,
+            terminated_early,
+        }
+    }
+
+    /// Performs a random walk on a graph where the first hop is picked
+    /// with `first_hop_weight` and the remaining hops with `weight_fun`.
+    ///
+    /// Other than that, it behaves like [`random_walk`](#method.random_walk).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let visited = graph.random_walk_with_first_hop(
+    ///     &1,
+    ///     2,
+    ///     |_, x| if *x == 3 { 1.0 } else { 0.0 },
+    ///     |_, _| 1.0
+    /// );
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![3, 1]);
+    /// ```
+    pub fn random_walk_with_first_hop(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        first_hop_weight: impl Fn(&T, &T) -> f32,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> LinkedList<T> {
+        let first_hop = Cell::new(true);
+        self.walk_with(
+            starting_node,
+            max_hops,
+            false,
+            |rng, candidates, current_node| {
+                if first_hop.replace(false) {
+                    weighted_sample(rng, candidates, |next_node| {
+                        first_hop_weight(current_node, next_node)
+                    })
+                } else {
+                    weighted_sample(rng, candidates, |next_node| {
+                        weight_fun(current_node, next_node)
+                    })
+                }
+            },
+        )
+    }
+
+    /// Performs a random walk on a graph that starts by traversing
+    /// the edge `node_a -> node_b`.
+    ///
+    /// The first hop is always `node_b`, and the remaining hops are picked
+    /// as in [`random_walk`](#method.random_walk). If the edge does not
+    /// exist, no node is visited.
+    ///
+    /// It returns the list of visited nodes in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::graph::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::new();
+    ///
+    /// graph.add_edge(&1, &2);
+    /// graph.add_edge(&1, &3);
+    /// let visited = graph.random_walk_from_edge(&1, &3, 2, |_, _| 1.0);
+    /// assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![3, 1]);
+    ///
+    /// let visited = graph.random_walk_from_edge(&2, &3, 2, |_, _| 1.0);
+    /// assert!(visited.is_empty());
+    /// ```
+    pub fn random_walk_from_edge(
+        &self,
+        node_a: &T,
+        node_b: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+    ) -> LinkedList<T> {
+        let has_edge = self
+            .data
+            .get(node_a)
+            .map(|succs| succs.contains(node_b))
+            .unwrap_or(false);
+        if max_hops == 0 || !has_edge {
+            return LinkedList::new();
+        }
+        let mut visited = self.random_walk(node_b, max_hops - 1, weight_fun);
+        visited.push_back(node_a.clone());
+        visited
+    }
+}
+
+/// Handle to a node of a graph, returned by
+/// [`Graph::node_entry`](struct.Graph.html#method.node_entry).
+pub struct NodeEntry<'a, T> {
+    graph: &'a mut Graph<T>,
+    node: T,
+}
+
+impl<'a, T: Eq + Clone + Hash> NodeEntry<'a, T> {
+    /// Returns the node of this entry.
+    pub fn node(&self) -> &T {
+        &self.node
+    }
+
+    /// Returns the degree of the node.
+    pub fn degree(&self) -> usize {
+        self.graph.degree(&self.node)
+    }
+
+    /// Adds an edge between this node and `other`, which is created if needed.
+    pub fn add_edge(&mut self, other: &T) -> &mut NodeEntry<'a, T> {
+        self.graph.add_edge(&self.node, other);
+        self
+    }
+
+    /// Removes the edge between this node and `other`, if it exists.
+    pub fn remove_edge(&mut self, other: &T) -> &mut NodeEntry<'a, T> {
+        self.graph.remove_edge(&self.node, other);
+        self
+    }
+}
+
+impl<T: Eq + Clone + Hash> Default for Graph<T> {
+    fn default() -> Graph<T> {
+        Graph::new()
+    }
+}
+
+impl<T: fmt::Debug + Eq + Hash> fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Graph {:?} / Max Degree = {:?}",
+            self.data, self.max_degree
+        )
+    }
+}
+
+/// Serialized form of a graph, with each edge listed only once.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedGraph<T> {
+    nodes: Vec<T>,
+    edges: Vec<(T, T, f32)>,
+}
+
+/// The graph is serialized as a list of nodes and a list of weighted
+/// edges. The random number generator factory is not serialized.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Eq + Clone + Hash> serde::Serialize for Graph<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut processed: HashSet<&T> = HashSet::new();
+        let mut edges: Vec<(T, T, f32)> = Vec::with_capacity(self.edge_count);
+        for (node, succs) in self.data.iter() {
+            for succ in succs.iter().filter(|succ| !processed.contains(succ)) {
+                let weight = self.edge_weight(node, succ).unwrap_or(1.0);
+                edges.push((node.clone(), succ.clone(), weight));
+            }
+            processed.insert(node);
+        }
+        SerializedGraph {
+            nodes: self.data.keys().cloned().collect(),
+            edges,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The cached information about the graph (e.g. the maximum degree) is
+/// recomputed after deserializing.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Eq + Clone + Hash> serde::Deserialize<'de> for Graph<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Graph<T>, D::Error> {
+        let serialized = SerializedGraph::<T>::deserialize(deserializer)?;
+        let mut graph = Graph::new();
+        graph.begin_batch();
+        for node in serialized.nodes.iter() {
+            graph.add_node(node);
+        }
+        for (node_a, node_b, weight) in serialized.edges.iter() {
+            graph.add_weighted_edge(node_a, node_b, *weight);
+        }
+        graph.end_batch();
+        Ok(graph)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn unknown_node_random_walk() {
         let graph: Graph<u32> = Graph::new();
-        let visited = graph.random_walk(&1, 200, |_, x| x.clone() as f32);
+        let visited = graph.random_walk(&1, 200, |_, x| *x as f32);
         assert_eq!(
             visited.len(),
             0,
@@ -268,7 +1629,7 @@ mod test {
     fn lone_node_random_walk() {
         let mut graph: Graph<u32> = Graph::new();
         graph.add_node(&1);
-        let visited = graph.random_walk(&1, 200, |_, x| x.clone() as f32);
+        let visited = graph.random_walk(&1, 200, |_, x| *x as f32);
         assert_eq!(
             visited.len(),
             1,
@@ -280,18 +1641,634 @@ mod test {
     #[test]
     fn sample_with_weights() {
         let mut rng = rand::thread_rng();
-        let mut list: LinkedList<&u8> = LinkedList::new();
-        list.push_front(&0);
-        list.push_front(&1);
-        let res1 = Graph::weighted_sample(&mut rng, list.clone(), |x| x.clone() as f32);
+        let list: Vec<&u8> = vec![&1, &0];
+        let res1 = weighted_sample(&mut rng, &list, |x| *x as f32);
         assert_eq!(res1.unwrap(), 1);
-        let res2 = Graph::weighted_sample(&mut rng, list.clone(), |x| {
-            1.0 + (-1.0 * (x.clone() as f32))
-        });
+        let res2 = weighted_sample(&mut rng, &list, |x| 1.0 - (*x as f32));
         assert_eq!(res2.unwrap(), 0);
-        let res3 = Graph::weighted_sample(&mut rng, list.clone(), |_| -1.0);
+        let res3 = weighted_sample(&mut rng, &list, |_| -1.0);
         assert_eq!(res3, None);
-        let res4 = Graph::weighted_sample(&mut rng, list.clone(), |_| 1.0);
+        let res4 = weighted_sample(&mut rng, &list, |_| 1.0);
         assert!(res4.unwrap() == 0 || res4.unwrap() == 1);
     }
+
+    #[test]
+    fn sorted_neighbors() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+        graph.add_edge(&1, &3);
+        graph.add_edge(&1, &4);
+        graph.add_edge(&1, &5);
+        graph.add_edge(&2, &3);
+
+        let neighbors = graph
+            .neighbors_sorted(&1, |_, x| (*x as f32 - 3.2).abs())
+            .iter()
+            .map(|(x, _)| *x)
+            .collect::<Vec<u32>>();
+        assert_eq!(neighbors, vec![5, 2, 4, 3]);
+    }
+
+    #[test]
+    fn rebuild_stale_index() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+        graph.add_edge(&1, &3);
+        graph.add_edge(&2, &3);
+        assert_eq!(graph.max_degree(), 2);
+
+        for succs in graph.data.values_mut() {
+            succs.clear();
+        }
+        graph.data.get_mut(&1).unwrap().insert(2);
+        graph.data.get_mut(&2).unwrap().insert(1);
+        assert_eq!(graph.max_degree(), 2);
+
+        graph.rebuild_index();
+        assert_eq!(graph.max_degree(), 1);
+
+        graph.data.clear();
+        graph.rebuild_index();
+        assert_eq!(graph.max_degree(), 0);
+    }
+
+    #[test]
+    fn non_backtracking_random_walk_explores_further() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..20 {
+            graph.add_edge(&i, &(i + 1));
+        }
+
+        let furthest = |visited: LinkedList<u32>| *visited.iter().max().unwrap();
+
+        let non_backtracking = furthest(graph.non_backtracking_random_walk(&0, 10, |_, _| 1.0));
+        assert_eq!(non_backtracking, 9);
+
+        let backtracking: u32 = (0..100)
+            .map(|_| furthest(graph.random_walk(&0, 10, |_, _| 1.0)))
+            .sum();
+        assert!(backtracking < 100 * non_backtracking);
+    }
+
+    #[test]
+    fn node_entry_edges() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&2, &3);
+
+        {
+            let mut entry = graph.node_entry(&1);
+            assert_eq!(entry.degree(), 0);
+            entry.add_edge(&2).add_edge(&3).add_edge(&4).add_edge(&4);
+            assert_eq!(entry.node(), &1);
+            assert_eq!(entry.degree(), 3);
+        }
+        assert_eq!(graph.degree(&1), 3);
+        assert_eq!(graph.degree(&2), 2);
+        assert_eq!(graph.degree(&4), 1);
+        assert_eq!(graph.max_degree(), 3);
+
+        graph.node_entry(&1).remove_edge(&4).remove_edge(&5);
+        assert_eq!(graph.degree(&1), 2);
+        assert_eq!(graph.degree(&4), 0);
+        assert_eq!(graph.max_degree(), 2);
+        assert!(!graph.contains(&5));
+    }
+
+    #[test]
+    fn sample_with_integer_weights() {
+        let mut rng = rand::thread_rng();
+        let list: Vec<&u64> = vec![&0, &1, &3];
+
+        let big_weight = |x: &u64| *x * (1 << 40);
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            let res = Graph::weighted_sample_u64(&mut rng, &list, big_weight).unwrap();
+            counts[res as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        let ratio = counts[3] as f64 / counts[1] as f64;
+        assert!(ratio > 2.5 && ratio < 3.5, "Unexpected ratio {}", ratio);
+
+        let res = Graph::weighted_sample_u64(&mut rng, &list, |_| u64::MAX);
+        assert!(res.is_some());
+        let res = Graph::weighted_sample_u64(&mut rng, &list, |_| 0);
+        assert_eq!(res, None);
+        let res = Graph::weighted_sample_u64(&mut rng, &list, |x| (1 << 25) + *x);
+        assert!(res.is_some());
+    }
+
+    #[test]
+    fn batched_max_degree() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+
+        graph.begin_batch();
+        for i in 3..10 {
+            graph.add_edge(&1, &i);
+        }
+        assert_eq!(graph.max_degree, 1);
+        assert_eq!(graph.max_degree(), 8);
+        graph.node_entry(&1).remove_edge(&9);
+        graph.end_batch();
+
+        assert_eq!(graph.max_degree, 7);
+        assert_eq!(graph.max_degree(), 7);
+    }
+
+    #[test]
+    fn excluded_edges_are_never_traversed() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..10 {
+            graph.add_edge(&i, &(i + 1));
+            graph.add_edge(&i, &(i + 10));
+        }
+
+        let visited = graph.random_walk_filtered(&0, 200, |from, to| {
+            if *from >= 10 || *to >= 10 {
+                None
+            } else {
+                Some(1.0)
+            }
+        });
+        assert_eq!(visited.len(), 200);
+        assert!(visited.iter().all(|x| *x < 10));
+
+        let visited = graph.random_walk_filtered(&0, 200, |_, _| None);
+        assert_eq!(visited.into_iter().collect::<Vec<u32>>(), vec![0]);
+    }
+
+    #[test]
+    fn edges_of_node() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+        graph.add_edge(&1, &3);
+        graph.add_edge(&2, &3);
+        graph.add_node(&4);
+
+        let mut edges = graph.edges_of(&1);
+        edges.sort_by_key(|(x, _)| *x);
+        assert_eq!(edges, vec![(2, 1.0), (3, 1.0)]);
+        assert!(graph.edges_of(&4).is_empty());
+    }
+
+    #[test]
+    fn reproducible_walks_with_seeded_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut graph: Graph<u32> =
+            Graph::with_rng_factory(|| Box::new(StdRng::seed_from_u64(7)) as Box<dyn RngCore>);
+        for i in 0..50 {
+            graph.add_edge(&i, &((i * 7 + 3) % 50));
+            graph.add_edge(&i, &((i * 11 + 5) % 50));
+        }
+
+        let first_walk = graph.random_walk(&0, 100, |_, _| 1.0);
+        assert_eq!(first_walk.len(), 100);
+        for _ in 0..10 {
+            assert_eq!(graph.random_walk(&0, 100, |_, _| 1.0), first_walk);
+        }
+    }
+
+    #[test]
+    fn bipartite_graphs() {
+        let mut graph: Graph<u32> = Graph::new();
+        assert!(graph.is_bipartite());
+
+        for i in 0..10 {
+            graph.add_edge(&i, &(i + 1));
+        }
+        graph.add_edge(&0, &9);
+        graph.add_node(&20);
+        assert!(graph.is_bipartite());
+
+        graph.add_edge(&20, &20);
+        assert!(!graph.is_bipartite());
+    }
+
+    #[test]
+    fn shrink_graph() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..1000 {
+            graph.add_edge(&(i % 10), &(i + 10));
+        }
+        let capacity = graph.capacity();
+        assert!(capacity >= 1010);
+
+        graph.data.retain(|node, _| *node < 20);
+        for succs in graph.data.values_mut() {
+            succs.retain(|node| *node < 20);
+        }
+        graph.shrink_to_fit();
+
+        assert!(graph.capacity() < capacity);
+        assert_eq!(graph.nodes().count(), 20);
+        assert_eq!(graph.successors(&0), [10].iter().cloned().collect());
+        assert_eq!(graph.successors(&10), [0].iter().cloned().collect());
+    }
+
+    #[test]
+    fn remove_isolated_nodes() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_edge(&1, &2);
+        graph.add_edge(&2, &3);
+        graph.add_edge(&4, &4);
+        graph.add_node(&5);
+        graph.add_node(&6);
+
+        assert_eq!(graph.remove_isolated(), 2);
+        assert_eq!(graph.nodes().count(), 4);
+        assert!(!graph.contains(&5));
+        assert!(!graph.contains(&6));
+        assert_eq!(graph.max_degree(), 2);
+        assert_eq!(graph.remove_isolated(), 0);
+    }
+
+    #[test]
+    fn stored_weights_random_walk() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 1..5 {
+            graph.add_edge(&0, &i);
+        }
+        graph.add_edge(&1, &2);
+
+        let mut stored_visits: HashMap<u32, i32> = HashMap::new();
+        let mut closure_visits: HashMap<u32, i32> = HashMap::new();
+        for _ in 0..4000 {
+            for node in graph.random_walk_stored(&0, 3) {
+                *stored_visits.entry(node).or_insert(0) += 1;
+            }
+            for node in graph.random_walk(&0, 3, |_, _| 1.0) {
+                *closure_visits.entry(node).or_insert(0) += 1;
+            }
+        }
+
+        assert_eq!(stored_visits.len(), 5);
+        for (node, visits) in stored_visits {
+            let expected = closure_visits[&node];
+            assert!(
+                (visits - expected).abs() < expected / 5,
+                "Node {} was visited {} times instead of {}",
+                node,
+                visits,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn walk_result_termination() {
+        let mut dead_end: Graph<u32> = Graph::new();
+        dead_end.add_edge(&1, &2);
+        dead_end.add_edge(&2, &3);
+        let result =
+            dead_end.random_walk_result(&1, 10, |from, to| if to > from { 1.0 } else { 0.0 });
+        assert!(result.terminated_early);
+        assert_eq!(
+            result.nodes.into_iter().collect::<Vec<u32>>(),
+            vec![3, 2, 1]
+        );
+
+        let result = dead_end.random_walk_result(&4, 10, |_, _| 1.0);
+        assert!(result.terminated_early);
+        assert!(result.nodes.is_empty());
+
+        let mut cycle: Graph<u32> = Graph::new();
+        cycle.add_edge(&1, &2);
+        cycle.add_edge(&2, &3);
+        cycle.add_edge(&3, &1);
+        let result = cycle.random_walk_result(&1, 10, |_, _| 1.0);
+        assert!(!result.terminated_early);
+        assert_eq!(result.nodes.len(), 10);
+
+        let result = cycle.random_walk_result(&1, 0, |_, _| 1.0);
+        assert!(!result.terminated_early);
+    }
+
+    #[test]
+    fn incremental_average_degree() {
+        let full_recompute = |graph: &Graph<u32>| {
+            let degree_sum: usize = graph.nodes().map(|node| graph.degree(node)).sum();
+            degree_sum as f64 / graph.nodes().count() as f64
+        };
+
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..50 {
+            graph.add_edge(&(i % 7), &(i % 11 + 100));
+        }
+        assert_eq!(graph.average_degree(), full_recompute(&graph));
+
+        graph.add_edge(&1, &101);
+        graph.add_edge(&101, &1);
+        graph.add_edge(&3, &3);
+        graph.add_node(&200);
+        assert_eq!(graph.average_degree(), full_recompute(&graph));
+
+        graph.node_entry(&1).remove_edge(&101).remove_edge(&101);
+        graph.node_entry(&3).remove_edge(&3);
+        graph.node_entry(&5).remove_edge(&999);
+        assert_eq!(graph.average_degree(), full_recompute(&graph));
+
+        graph.begin_batch();
+        graph.add_edge(&4, &4);
+        graph.node_entry(&0).remove_edge(&100);
+        graph.end_batch();
+        assert_eq!(graph.average_degree(), full_recompute(&graph));
+
+        let (edge_count, self_loop_count) = (graph.edge_count, graph.self_loop_count);
+        graph.rebuild_index();
+        assert_eq!(graph.edge_count, edge_count);
+        assert_eq!(graph.self_loop_count, self_loop_count);
+    }
+
+    #[test]
+    fn batched_random_walks() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 1..5 {
+            graph.add_edge(&0, &i);
+        }
+        let weight_fun = |_: &u32, to: &u32| *to as f32;
+
+        let walks = graph.random_walks(&0, 2000, 2, weight_fun);
+        assert_eq!(walks.len(), 2000);
+        assert!(walks.iter().all(|walk| walk.len() == 2 && walk[1] == 0));
+        assert!(graph.random_walks(&0, 0, 2, weight_fun).is_empty());
+
+        let mut batched = [0; 5];
+        for walk in walks {
+            batched[walk[0] as usize] += 1;
+        }
+        let mut repeated = [0; 5];
+        for _ in 0..2000 {
+            repeated[*graph.random_walk(&0, 2, weight_fun).front().unwrap() as usize] += 1;
+        }
+        for i in 1..5 {
+            let expected = 2000.0 * i as f64 / 10.0;
+            assert!((batched[i] as f64 - expected).abs() < expected * 0.25);
+            assert!((repeated[i] as f64 - expected).abs() < expected * 0.25);
+        }
+    }
+
+    #[test]
+    fn path_graph_diameter() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..10 {
+            graph.add_edge(&i, &(i + 1));
+        }
+        assert_eq!(graph.diameter(), Some(10));
+        assert_eq!(graph.eccentricity(&0), Some(10));
+        assert_eq!(graph.eccentricity(&5), Some(5));
+
+        // Disconnected components are measured separately
+        graph.add_edge(&100, &101);
+        graph.add_node(&200);
+        assert_eq!(graph.diameter(), Some(10));
+        assert_eq!(graph.eccentricity(&100), Some(1));
+        assert_eq!(graph.eccentricity(&200), Some(0));
+
+        // A cycle halves the distances
+        graph.add_edge(&10, &0);
+        assert_eq!(graph.diameter(), Some(5));
+
+        let mut large: Graph<u32> = Graph::new();
+        for i in 0..2000 {
+            large.add_edge(&i, &(i + 1));
+        }
+        assert_eq!(large.diameter(), Some(2000));
+    }
+
+    #[test]
+    fn remove_edge_updates_max_degree() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 1..5 {
+            graph.add_edge(&0, &i);
+        }
+        graph.add_edge(&1, &2);
+        graph.add_edge(&1, &3);
+        graph.add_edge(&5, &5);
+        assert_eq!(graph.max_degree(), 4);
+        assert_eq!(graph.edge_count, 7);
+
+        assert!(!graph.remove_edge(&2, &3));
+        assert!(!graph.remove_edge(&0, &100));
+        assert!(!graph.remove_edge(&100, &0));
+        assert!(!graph.contains(&100));
+        assert_eq!(graph.max_degree(), 4);
+        assert_eq!(graph.edge_count, 7);
+
+        assert!(graph.remove_edge(&4, &0));
+        assert_eq!(graph.max_degree(), 3);
+        assert!(!graph.successors(&0).contains(&4));
+        assert!(graph.successors(&4).is_empty());
+        assert!(graph.contains(&4));
+
+        assert!(graph.remove_edge(&0, &1));
+        assert_eq!(graph.degree(&0), 2);
+        assert_eq!(graph.degree(&1), 2);
+        assert_eq!(graph.max_degree(), 2);
+
+        assert!(graph.remove_edge(&0, &2));
+        assert!(graph.remove_edge(&1, &2));
+        assert_eq!(graph.max_degree(), 2);
+
+        assert!(graph.remove_edge(&5, &5));
+        assert_eq!(graph.self_loop_count, 0);
+        assert_eq!(graph.edge_count, 2);
+
+        graph.begin_batch();
+        assert!(graph.remove_edge(&0, &3));
+        graph.end_batch();
+        assert_eq!(graph.max_degree(), 1);
+        assert_eq!(graph.edge_count, 1);
+    }
+
+    #[test]
+    fn add_edge_returns_degrees() {
+        let mut graph: Graph<u32> = Graph::new();
+        let pairs = [(1, 2), (1, 3), (2, 3), (1, 3), (4, 4), (4, 1)];
+        for (a, b) in pairs.iter() {
+            let degrees = graph.add_edge_degrees(a, b);
+            assert_eq!(degrees, (graph.degree(a), graph.degree(b)));
+        }
+        assert_eq!(graph.degree(&1), 3);
+        assert_eq!(graph.degree(&4), 2);
+        assert_eq!(graph.max_degree(), 3);
+    }
+
+    #[test]
+    fn seeded_random_walks() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 0..50 {
+            graph.add_edge(&i, &((i * 7) % 50));
+            graph.add_edge(&i, &((i + 1) % 50));
+        }
+
+        let walks = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..10)
+                .map(|_| graph.random_walk_with_rng(&0, 20, |_, to| (*to % 4 + 1) as f32, &mut rng))
+                .collect::<Vec<LinkedList<u32>>>()
+        };
+        let first_run = walks(42);
+        assert!(first_run.iter().all(|walk| walk.len() == 20));
+        assert_eq!(walks(42), first_run);
+        assert_ne!(walks(43), first_run);
+    }
+
+    #[test]
+    fn weighted_edges() {
+        let mut graph: Graph<u32> = Graph::new();
+        graph.add_weighted_edge(&0, &1, 5.0);
+        graph.add_weighted_edge(&0, &2, 2.0);
+        graph.add_edge(&0, &3);
+        assert_eq!(graph.edge_count, 3);
+        assert_eq!(graph.edge_weight(&1, &0), Some(5.0));
+        assert_eq!(graph.edge_weight(&0, &3), Some(1.0));
+        assert_eq!(graph.edge_weight(&1, &2), None);
+        assert_eq!(graph.edge_weight(&4, &0), None);
+
+        let mut edges = graph.edges_of(&0);
+        edges.sort_by_key(|(node, _)| *node);
+        assert_eq!(edges, vec![(1, 5.0), (2, 2.0), (3, 1.0)]);
+
+        // Overwriting, removing and re-adding an edge resets the weight
+        graph.add_weighted_edge(&0, &2, 3.0);
+        assert_eq!(graph.edge_weight(&2, &0), Some(3.0));
+        graph.add_edge(&2, &0);
+        assert_eq!(graph.edge_weight(&0, &2), Some(1.0));
+        assert!(graph.remove_edge(&1, &0));
+        assert_eq!(graph.edge_weight(&0, &1), None);
+        graph.add_edge(&0, &1);
+        assert_eq!(graph.edge_weight(&0, &1), Some(1.0));
+        assert!(graph.edge_weights.is_empty());
+        assert_eq!(graph.edge_count, 3);
+
+        // Walks follow the stored weights
+        graph.add_weighted_edge(&0, &1, 8.0);
+        let counts =
+            |visited: &LinkedList<u32>, node: u32| visited.iter().filter(|x| **x == node).count();
+        let visited = graph.random_walk_stored(&0, 250);
+        assert_eq!(visited.len(), 250);
+        assert!(counts(&visited, 1) > counts(&visited, 2) + counts(&visited, 3));
+        let visited = graph.random_walk(&0, 250, |from, to| {
+            graph.edge_weight(from, to).unwrap_or(0.0)
+        });
+        assert!(counts(&visited, 1) > counts(&visited, 2) + counts(&visited, 3));
+    }
+
+    #[test]
+    fn node_and_edge_counts() {
+        let mut graph: Graph<u32> = Graph::new();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(graph.edge_count(), 0);
+
+        graph.add_edge(&1, &2);
+        graph.add_edge(&2, &3);
+        graph.add_edge(&3, &1);
+        graph.add_edge(&1, &2);
+        graph.add_node(&4);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+
+        graph.add_edge(&4, &4);
+        assert_eq!(graph.edge_count(), 4);
+        let degree_sum: usize = graph.nodes().map(|node| graph.degree(node)).sum();
+        assert_eq!(degree_sum, 2 * graph.edge_count() - 1);
+
+        assert!(graph.remove_edge(&4, &4));
+        assert!(graph.remove_edge(&2, &1));
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.remove_isolated(), 1);
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn sample_with_cumulative_weights() {
+        let mut rng = rand::thread_rng();
+        let list: Vec<&u32> = vec![&0, &1, &2, &3];
+        let weight = |x: &u32| match x {
+            0 => 0.0,
+            1 => 1.0,
+            2 => f32::NAN,
+            _ => 3.0,
+        };
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[weighted_sample(&mut rng, &list, weight).unwrap() as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[2], 0);
+        let ratio = counts[3] as f64 / counts[1] as f64;
+        assert!(ratio > 2.5 && ratio < 3.5, "Unexpected ratio {}", ratio);
+        assert_eq!(weighted_sample(&mut rng, &[] as &[&u32], weight), None);
+
+        // Weight functions can sample as well
+        let res = weighted_sample(&mut rand::thread_rng(), &list, |x| {
+            let inner = weighted_sample(&mut rand::thread_rng(), &list, weight);
+            if inner == Some(*x) {
+                1.0
+            } else {
+                0.5
+            }
+        });
+        assert!(res.is_some());
+    }
+
+    #[test]
+    fn remove_node_updates_counts() {
+        let mut graph: Graph<u32> = Graph::new();
+        for i in 1..5 {
+            graph.add_weighted_edge(&0, &i, i as f32);
+        }
+        graph.add_edge(&1, &2);
+        graph.add_edge(&0, &0);
+        graph.add_edge(&5, &6);
+        assert_eq!(graph.max_degree(), 5);
+        assert_eq!(graph.edge_count(), 7);
+
+        assert!(graph.remove_node(&0));
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.self_loop_count, 0);
+        assert_eq!(graph.max_degree(), 1);
+        assert_eq!(graph.degree(&3), 0);
+        assert!(graph.edge_weights.is_empty());
+        assert!(graph
+            .nodes()
+            .all(|node| !graph.successors(node).contains(&0)));
+
+        // Removing a node without the maximum degree keeps it
+        graph.add_edge(&1, &3);
+        assert!(graph.remove_node(&6));
+        assert_eq!(graph.max_degree(), 2);
+        assert!(!graph.remove_node(&6));
+    }
+
+    #[test]
+    fn k_hop_neighborhoods() {
+        let mut graph: Graph<u32> = Graph::new();
+        // A path 0 - 1 - ... - 9, with a cycle 0 - 10 - 11 - 0
+        for i in 0..9 {
+            graph.add_edge(&i, &(i + 1));
+        }
+        graph.add_edge(&0, &10);
+        graph.add_edge(&10, &11);
+        graph.add_edge(&11, &0);
+        graph.add_node(&20);
+
+        let sorted = |nodes: HashSet<u32>| {
+            let mut nodes = nodes.into_iter().collect::<Vec<u32>>();
+            nodes.sort();
+            nodes
+        };
+        assert_eq!(sorted(graph.neighbors_within(&0, 0)), vec![0]);
+        assert_eq!(sorted(graph.neighbors_within(&0, 1)), vec![0, 1, 10, 11]);
+        assert_eq!(sorted(graph.neighbors_within(&0, 2)), vec![0, 1, 2, 10, 11]);
+        assert_eq!(sorted(graph.neighbors_within(&5, 2)), vec![3, 4, 5, 6, 7]);
+        assert_eq!(graph.neighbors_within(&0, 100).len(), 12);
+        assert_eq!(sorted(graph.neighbors_within(&20, 3)), vec![20]);
+        assert!(graph.neighbors_within(&30, 3).is_empty());
+        assert!(graph.neighbors_within(&30, 0).is_empty());
+    }
 }
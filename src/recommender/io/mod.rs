@@ -0,0 +1,160 @@
+//! # IO
+//!
+//! The `io` module is a collection of utilities to load data into a
+//! recommender from external files.
+//!
+//! This module is only available with the `csv` feature.
+
+use std::io;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Columns of a CSV file with one object per row.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CsvColumns {
+    object: usize,
+    tags: usize,
+    tag_separator: char,
+}
+
+impl CsvColumns {
+    /// Creates a new description of the columns, where the `object`
+    /// column contains the object and the `tags` column contains its tags
+    /// split by `tag_separator` (e.g. `"Action, Drama"`).
+    pub fn new(object: usize, tags: usize, tag_separator: char) -> CsvColumns {
+        CsvColumns {
+            object,
+            tags,
+            tag_separator,
+        }
+    }
+}
+
+impl Recommender<String> {
+    /// Adds the objects, tags and edges of a CSV file (with a header row)
+    /// to this recommender, returning how many new edges were added.
+    ///
+    /// Existing objects, tags and edges are kept, and adding an edge that
+    /// already exists has no effect, so the same file can be merged
+    /// multiple times. Rows with an empty object are ignored. Empty tags
+    /// are ignored.
+    ///
+    /// The rows are merged as they are read, so if a row is malformed an
+    /// error is returned, but the rows before it are kept in this
+    /// recommender.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::io::CsvColumns;
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let data = "name,genre\nThe Raid,\"Action, Martial Arts\"\nRocky,Action\n";
+    /// let columns = CsvColumns::new(0, 1, ',');
+    ///
+    /// assert_eq!(recommender.merge_from_csv(data.as_bytes(), &columns).unwrap(), 3);
+    /// assert_eq!(recommender.merge_from_csv(data.as_bytes(), &columns).unwrap(), 0);
+    /// ```
+    pub fn merge_from_csv(
+        &mut self,
+        reader: impl io::Read,
+        columns: &CsvColumns,
+    ) -> csv::Result<usize> {
+        let mut csv_reader = csv::Reader::from_reader(reader);
+        let mut new_edges = 0;
+        for record in csv_reader.records() {
+            let record = record?;
+            let object = match record.get(columns.object).map(|object| object.trim()) {
+                Some(object) if !object.is_empty() => String::from(object),
+                _ => continue,
+            };
+            let object_node = RecommenderNode::Object(object.clone());
+            let previous_degree = self.graph.degree(&object_node);
+            self.add_object(&object);
+            let tags = record
+                .get(columns.tags)
+                .unwrap_or("")
+                .split(columns.tag_separator)
+                .map(|tag| tag.trim())
+                .filter(|tag| !tag.is_empty());
+            for tag in tags {
+                self.tag_object(&object, tag);
+            }
+            new_edges += self.graph.degree(&object_node) - previous_degree;
+        }
+        Ok(new_edges)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_csv_delta() {
+        let mut recommender: Recommender<String> = Recommender::new();
+        let columns = CsvColumns::new(1, 2, ',');
+
+        let base = "\
+id,name,genre
+1,The Raid,\"Action, Martial Arts\"
+2,Rocky,\"Action, Drama\"
+";
+        let delta = "\
+id,name,genre
+2,Rocky,\"Drama, Sports\"
+3,Monty Python and The Holy Grail,Comedy
+4,Untagged,
+5,  ,Comedy
+";
+
+        assert_eq!(
+            recommender
+                .merge_from_csv(base.as_bytes(), &columns)
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            recommender
+                .merge_from_csv(delta.as_bytes(), &columns)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            recommender
+                .merge_from_csv(delta.as_bytes(), &columns)
+                .unwrap(),
+            0
+        );
+
+        let rocky = RecommenderNode::Object(String::from("Rocky"));
+        let rocky_tags = recommender.graph.successors(&rocky);
+        assert_eq!(rocky_tags.len(), 3);
+        for tag in ["Action", "Drama", "Sports"].iter() {
+            assert!(rocky_tags.contains(&RecommenderNode::Tag(String::from(*tag))));
+        }
+        assert!(recommender
+            .graph
+            .contains(&RecommenderNode::Object(String::from("Untagged"))));
+        assert_eq!(recommender.graph.nodes().count(), 9);
+
+        let malformed = "id,name\n1,The Raid,Action\n";
+        assert!(recommender
+            .merge_from_csv(malformed.as_bytes(), &columns)
+            .is_err());
+
+        let partial = "id,name,genre\n6,Heat,Crime\n7,Broken\n8,Alien,Horror\n";
+        assert!(recommender
+            .merge_from_csv(partial.as_bytes(), &columns)
+            .is_err());
+        assert!(recommender
+            .graph
+            .contains(&RecommenderNode::Object(String::from("Heat"))));
+        assert!(!recommender
+            .graph
+            .contains(&RecommenderNode::Object(String::from("Alien"))));
+    }
+}
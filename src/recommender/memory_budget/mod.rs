@@ -0,0 +1,362 @@
+//! # Memory Budget
+//!
+//! The `memory_budget` module is a collection of utilities to bound the
+//! memory used to accumulate the visits of the walks, so that a
+//! pathological query can't exhaust the memory of the process.
+
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// What to do when the accumulated visits exceed the memory budget.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BudgetPolicy {
+    /// Abort the computation with a [`MemoryBudgetExceeded`] error.
+    ///
+    /// [`MemoryBudgetExceeded`]: struct.MemoryBudgetExceeded.html
+    Error,
+    /// Keep going, but only track the most visited candidates that fit
+    /// in the budget. The counts of the tracked candidates become upper
+    /// bounds of the real counts.
+    TopK,
+}
+
+/// Limit on the memory used to accumulate the visits of each query.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryBudget {
+    max_candidates_memory: Option<usize>,
+    policy: BudgetPolicy,
+}
+
+impl MemoryBudget {
+    /// Creates a new budget of `max_candidates_memory` bytes per query
+    /// (or unlimited, if `None`).
+    ///
+    /// The budget is converted to a maximum number of accumulated
+    /// entries, each one counted as `size_of::<K>() + size_of::<u32>()`
+    /// bytes (where `K` is the type of the nodes). This caps the number of
+    /// entries, not the real memory used: the overhead of the maps, the
+    /// bookkeeping of [`BudgetPolicy::TopK`] and the heap memory owned by
+    /// the nodes (e.g. the contents of a `String`) are not included.
+    ///
+    /// [`BudgetPolicy::TopK`]: enum.BudgetPolicy.html#variant.TopK
+    pub fn new(max_candidates_memory: Option<usize>, policy: BudgetPolicy) -> MemoryBudget {
+        MemoryBudget {
+            max_candidates_memory,
+            policy,
+        }
+    }
+
+    /// Maximum memory, in bytes, used to accumulate the visits of a query.
+    pub fn max_candidates_memory(&self) -> Option<usize> {
+        self.max_candidates_memory
+    }
+
+    /// What to do when the budget is exceeded.
+    pub fn policy(&self) -> BudgetPolicy {
+        self.policy
+    }
+
+    fn max_candidates<K>(&self) -> Option<usize> {
+        let entry_size = mem::size_of::<K>() + mem::size_of::<u32>();
+        self.max_candidates_memory
+            .map(|bytes| bytes / entry_size.max(1))
+    }
+}
+
+/// Error returned when the accumulated visits of a query don't fit in
+/// the memory budget.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryBudgetExceeded {
+    /// Maximum number of candidates that fit in the budget.
+    pub max_candidates: usize,
+}
+
+impl fmt::Display for MemoryBudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Memory budget exceeded: more than {} candidates",
+            self.max_candidates
+        )
+    }
+}
+
+impl error::Error for MemoryBudgetExceeded {}
+
+/// Visit counter that never holds more than `max_candidates` entries.
+struct BudgetCounter<K> {
+    max_candidates: Option<usize>,
+    policy: BudgetPolicy,
+    counts: HashMap<K, u32>,
+    /// Slot of each item in `items`, only used by the `TopK` policy.
+    slots: HashMap<K, usize>,
+    items: Vec<K>,
+    /// Counts and slots of the items, with the weakest one on top. Every
+    /// increment pushes a new entry, so entries whose count is outdated
+    /// are skipped (and dropped when the heap grows too much).
+    weakest: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+impl<K: Eq + Clone + Hash> BudgetCounter<K> {
+    fn new(budget: &MemoryBudget) -> BudgetCounter<K> {
+        BudgetCounter {
+            max_candidates: budget.max_candidates::<K>(),
+            policy: budget.policy,
+            counts: HashMap::new(),
+            slots: HashMap::new(),
+            items: Vec::new(),
+            weakest: BinaryHeap::new(),
+        }
+    }
+
+    /// Counts one more visit to an item, failing if the item doesn't fit
+    /// and the policy is `BudgetPolicy::Error`.
+    fn increment(&mut self, item: K) -> Result<(), MemoryBudgetExceeded> {
+        let tracks_weakest = self.policy == BudgetPolicy::TopK && self.max_candidates.is_some();
+        if let Some(count) = self.counts.get_mut(&item) {
+            *count += 1;
+            if tracks_weakest {
+                let count = *count;
+                self.push_weakest(count, self.slots[&item]);
+            }
+            return Ok(());
+        }
+        match self.max_candidates {
+            Some(max_candidates) if self.counts.len() >= max_candidates => match self.policy {
+                BudgetPolicy::Error => Err(MemoryBudgetExceeded { max_candidates }),
+                BudgetPolicy::TopK => {
+                    // Space-Saving: the new item replaces the weakest one,
+                    // inheriting its count.
+                    if let Some((slot, weakest_count)) = self.pop_weakest() {
+                        let weakest_item = mem::replace(&mut self.items[slot], item.clone());
+                        self.counts.remove(&weakest_item);
+                        self.slots.remove(&weakest_item);
+                        self.counts.insert(item.clone(), weakest_count + 1);
+                        self.slots.insert(item, slot);
+                        self.push_weakest(weakest_count + 1, slot);
+                    }
+                    Ok(())
+                }
+            },
+            _ => {
+                self.counts.insert(item.clone(), 1);
+                if tracks_weakest {
+                    let slot = self.items.len();
+                    self.items.push(item.clone());
+                    self.slots.insert(item, slot);
+                    self.push_weakest(1, slot);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn push_weakest(&mut self, count: u32, slot: usize) {
+        self.weakest.push(Reverse((count, slot)));
+        if self.weakest.len() > 2 * self.counts.len() + 1 {
+            let counts = &self.counts;
+            self.weakest = self
+                .items
+                .iter()
+                .enumerate()
+                .map(|(slot, item)| Reverse((counts[item], slot)))
+                .collect();
+        }
+    }
+
+    fn pop_weakest(&mut self) -> Option<(usize, u32)> {
+        while let Some(Reverse((count, slot))) = self.weakest.pop() {
+            if self.counts.get(&self.items[slot]) == Some(&count) {
+                return Some((slot, count));
+            }
+        }
+        None
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Same as [`recommendations`], but the visits of each query are
+    /// accumulated within the given memory `budget`.
+    ///
+    /// When the budget is exceeded, either an error is returned or only
+    /// the best candidates are kept, according to the budget policy.
+    ///
+    /// [`recommendations`]: struct.Recommender.html#method.recommendations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::memory_budget::BudgetPolicy;
+    /// use pixie_rust::recommender::memory_budget::MemoryBudget;
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let queries = [RecommenderNode::Object(raid)];
+    ///
+    /// let recommendations = recommender.recommendations_with_memory_budget(
+    ///     &queries,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &MemoryBudget::new(None, BudgetPolicy::Error)
+    /// );
+    /// assert!(recommendations.unwrap().contains(&RecommenderNode::Object(rocky)));
+    ///
+    /// let recommendations = recommender.recommendations_with_memory_budget(
+    ///     &queries,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &MemoryBudget::new(Some(0), BudgetPolicy::Error)
+    /// );
+    /// assert!(recommendations.is_err());
+    /// ```
+    pub fn recommendations_with_memory_budget(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        budget: &MemoryBudget,
+    ) -> Result<Vec<RecommenderNode<T>>, MemoryBudgetExceeded> {
+        let exceeded: Cell<Option<MemoryBudgetExceeded>> = Cell::new(None);
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                let mut acc: BudgetCounter<RecommenderNode<T>> = BudgetCounter::new(budget);
+                if exceeded.get().is_some() {
+                    return acc.counts;
+                }
+                Recommender::accumulate_walks(
+                    steps,
+                    || {
+                        if exceeded.get().is_some() {
+                            return Default::default();
+                        }
+                        self.graph.random_walk(q, depth, |from, to| {
+                            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                        })
+                    },
+                    |visits| {
+                        for visited in visits {
+                            if let Err(err) = acc.increment(visited) {
+                                exceeded.set(Some(err));
+                                return;
+                            }
+                        }
+                    },
+                );
+                acc.counts
+            });
+
+        match exceeded.get() {
+            Some(err) => Err(err),
+            None => {
+                Ok(self.rank_recommendations(&all_recommendations, |node| !queries.contains(node)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn enforce_memory_budget() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..1000 {
+            recommender.tag_object(&obj, "Numbers");
+        }
+        let entry_size = mem::size_of::<RecommenderNode<u32>>() + mem::size_of::<u32>();
+        let queries = [RecommenderNode::Object(0)];
+
+        let result = recommender.recommendations_with_memory_budget(
+            &queries,
+            3,
+            3000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &MemoryBudget::new(Some(10 * entry_size), BudgetPolicy::Error),
+        );
+        assert_eq!(result, Err(MemoryBudgetExceeded { max_candidates: 10 }));
+
+        let result = recommender
+            .recommendations_with_memory_budget(
+                &queries,
+                3,
+                3000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                &MemoryBudget::new(Some(10 * entry_size), BudgetPolicy::TopK),
+            )
+            .unwrap();
+        assert!(!result.is_empty());
+        assert!(result.len() <= 10);
+        assert!(result.contains(&RecommenderNode::Tag(String::from("Numbers"))));
+
+        let result = recommender
+            .recommendations_with_memory_budget(
+                &queries,
+                3,
+                3000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                &MemoryBudget::new(None, BudgetPolicy::Error),
+            )
+            .unwrap();
+        assert!(result.len() > 10);
+    }
+
+    #[test]
+    fn budget_counter_keeps_heavy_hitters() {
+        let mut counter: BudgetCounter<u32> =
+            BudgetCounter::new(&MemoryBudget::new(Some(16), BudgetPolicy::TopK));
+        assert_eq!(counter.max_candidates, Some(2));
+        for _ in 0..100 {
+            counter.increment(1).unwrap();
+        }
+        for i in 2..100 {
+            counter.increment(i).unwrap();
+        }
+        assert_eq!(counter.counts.len(), 2);
+        assert_eq!(counter.counts[&1], 100);
+        assert!(counter.weakest.len() <= 2 * 2 + 1);
+
+        // The weakest item is the one that gets replaced
+        let mut counter: BudgetCounter<u32> =
+            BudgetCounter::new(&MemoryBudget::new(Some(24), BudgetPolicy::TopK));
+        assert_eq!(counter.max_candidates, Some(3));
+        for (item, visits) in [(1, 5), (2, 1), (3, 3)].iter() {
+            for _ in 0..*visits {
+                counter.increment(*item).unwrap();
+            }
+        }
+        counter.increment(4).unwrap();
+        assert!(!counter.counts.contains_key(&2));
+        assert_eq!(counter.counts[&4], 2);
+        counter.increment(5).unwrap();
+        assert!(!counter.counts.contains_key(&4));
+        assert_eq!(counter.counts[&5], 3);
+        assert_eq!(counter.counts[&1], 5);
+    }
+}
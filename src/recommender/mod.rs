@@ -3,14 +3,51 @@
 //! The `recommender` module is a collection of utilities to create
 //! a recommender and give recommendations.
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::LinkedList;
+use std::error;
 use std::fmt;
 use std::hash::Hash;
+
+use rand::Rng;
+use std::sync::Arc;
+use std::sync::RwLock;
 use std::vec::Vec;
 
+pub mod batch;
+pub mod comparison;
+pub mod digraph;
+pub mod early_stopping;
+pub mod exact;
+pub mod explanation;
+pub mod exploration;
+pub mod fingerprint;
 pub mod graph;
+#[cfg(feature = "csv")]
+pub mod io;
+pub mod memory_budget;
+pub mod ordered_graph;
+pub mod pagination;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod shared;
+pub mod similarity_index;
+pub mod sketch;
+#[cfg(feature = "stream")]
+pub mod stream;
+pub mod time_budget;
+pub mod validation;
+pub mod visit_model;
+use self::early_stopping::EarlyStopping;
+use self::early_stopping::WalkSchedule;
+use self::graph::sanitize_weight;
 use self::graph::Graph;
+use self::sketch::SketchConfig;
+use self::sketch::TopKCounter;
 
 /// Nodes to be used for recommendations.
 ///
@@ -18,15 +55,241 @@ use self::graph::Graph;
 /// an `Object` (e.g. a product).
 ///
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RecommenderNode<T> {
     Tag(String),
     Object(T),
 }
 
+impl<T> RecommenderNode<T> {
+    /// Creates a new tag node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let node: RecommenderNode<u32> = RecommenderNode::tag("Action");
+    /// assert_eq!(node, RecommenderNode::Tag(String::from("Action")));
+    /// ```
+    pub fn tag(tag: &str) -> RecommenderNode<T> {
+        RecommenderNode::Tag(String::from(tag))
+    }
+
+    /// Returns the object of this node, if it is an object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// assert_eq!(RecommenderNode::from(1).as_object(), Some(&1));
+    /// assert_eq!(RecommenderNode::<u32>::tag("Action").as_object(), None);
+    /// ```
+    pub fn as_object(&self) -> Option<&T> {
+        match self {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj),
+        }
+    }
+
+    /// Returns the name of this node, if it is a tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// assert_eq!(RecommenderNode::<u32>::tag("Action").as_tag(), Some("Action"));
+    /// assert_eq!(RecommenderNode::from(1).as_tag(), None);
+    /// ```
+    pub fn as_tag(&self) -> Option<&str> {
+        match self {
+            RecommenderNode::Tag(tag) => Some(tag),
+            RecommenderNode::Object(_) => None,
+        }
+    }
+}
+
+impl<T> From<T> for RecommenderNode<T> {
+    fn from(object: T) -> RecommenderNode<T> {
+        RecommenderNode::Object(object)
+    }
+}
+
+/// Strategy used to pick the first object when a walk starts from a tag.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FirstHopStrategy {
+    /// Use the tag to object weight function, as in any other hop.
+    Weighted,
+    /// Pick any object of the tag with the same probability.
+    Uniform,
+    /// Pick one of the objects of the tag with the most tags.
+    TopDegree,
+}
+
+/// Kinds of nodes whose visits are counted during the walks.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AccumulationMode {
+    /// Count the visits to every node.
+    All,
+    /// Only count the visits to tags, which saves memory and time when
+    /// only related tags are needed.
+    TagsOnly,
+}
+
+/// How the visits of the walks of a single query are combined into a
+/// score.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum WalkAggregation {
+    /// Sum the visits of every walk.
+    Sum,
+    /// Keep the largest number of visits in a single walk.
+    Max,
+    /// Count the walks that visited the node, which is proportional to
+    /// the fraction of walks that visited it. This is more robust to walks
+    /// that get stuck in a small region of the graph.
+    VisitRate,
+}
+
+impl WalkAggregation {
+    fn record<N: Eq + Hash>(self, acc: &mut HashMap<N, u32>, visits: LinkedList<N>) {
+        match self {
+            WalkAggregation::Sum => {
+                for visited in visits {
+                    *acc.entry(visited).or_insert(0) += 1;
+                }
+            }
+            WalkAggregation::Max => {
+                let mut walk_counts: HashMap<N, u32> = HashMap::new();
+                for visited in visits {
+                    *walk_counts.entry(visited).or_insert(0) += 1;
+                }
+                for (visited, count) in walk_counts {
+                    let entry = acc.entry(visited).or_insert(0);
+                    *entry = (*entry).max(count);
+                }
+            }
+            WalkAggregation::VisitRate => {
+                let walk_visits: HashSet<N> = visits.into_iter().collect();
+                for visited in walk_visits {
+                    *acc.entry(visited).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+}
+
+/// What to do with queries that are not part of the recommender.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum UnknownQueryPolicy {
+    /// Ignore the unknown queries, and use only the known ones. A warning
+    /// is logged with the [`log`](https://docs.rs/log) crate.
+    Skip,
+    /// Fail with an [`UnknownQuery`](struct.UnknownQuery.html) error.
+    Error,
+}
+
+/// How to order objects and tags with the same score.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TieBreak {
+    /// Keep the ties in an unspecified order.
+    Unordered,
+    /// Rank objects before tags with the same score.
+    ObjectsFirst,
+    /// Rank tags before objects with the same score.
+    TagsFirst,
+}
+
+impl TieBreak {
+    /// Position of a node among the nodes with the same score.
+    fn rank<T>(self, node: &RecommenderNode<T>) -> u8 {
+        match (self, node) {
+            (TieBreak::ObjectsFirst, RecommenderNode::Tag(_)) => 1,
+            (TieBreak::TagsFirst, RecommenderNode::Object(_)) => 1,
+            _ => 0,
+        }
+    }
+
+    /// Sorts nodes by descending score, ordering the ties between objects
+    /// and tags. The sort is stable, so other ties keep their order.
+    fn sort<T>(self, scored: &mut [(RecommenderNode<T>, f64)]) {
+        scored.sort_by(|(node_a, a), (node_b, b)| {
+            b.partial_cmp(a)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.rank(node_a).cmp(&self.rank(node_b)))
+        });
+    }
+}
+
+/// Error returned when some queries are not part of the recommender.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnknownQuery<T> {
+    /// Queries that are not part of the recommender, in the order that
+    /// they were given.
+    pub queries: Vec<RecommenderNode<T>>,
+}
+
+impl<T: fmt::Debug> fmt::Display for UnknownQuery<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Unknown queries: {:?}", self.queries)
+    }
+}
+
+impl<T: fmt::Debug> error::Error for UnknownQuery<T> {}
+
+type ObjectToTagWeight<'a, T> = Box<dyn Fn(&T, &String) -> f32 + 'a>;
+type TagToObjectWeight<'a, T> = Box<dyn Fn(&String, &T) -> f32 + 'a>;
+
+/// Pair of weight functions used to walk between objects and tags.
+pub struct WeightPair<'a, T> {
+    object_to_tag: ObjectToTagWeight<'a, T>,
+    tag_to_object: TagToObjectWeight<'a, T>,
+}
+
+impl<'a, T> WeightPair<'a, T> {
+    /// Creates a new pair of weight functions.
+    pub fn new(
+        object_to_tag_weight: impl Fn(&T, &String) -> f32 + 'a,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32 + 'a,
+    ) -> WeightPair<'a, T> {
+        WeightPair {
+            object_to_tag: Box::new(object_to_tag_weight),
+            tag_to_object: Box::new(tag_to_object_weight),
+        }
+    }
+
+    /// Creates a pair of weight functions where all edges have the same weight.
+    pub fn uniform() -> WeightPair<'a, T> {
+        WeightPair::new(|_, _| 1.0, |_, _| 1.0)
+    }
+}
+
+impl<'a, T> fmt::Debug for WeightPair<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WeightPair")
+    }
+}
+
+/// Visits to a node, along with the tags that were visited right before it.
+#[derive(Default)]
+struct TaggedVisits {
+    visits: u32,
+    via_tags: HashMap<String, u32>,
+}
+
+/// Tags that were visited right before each node, with their counts.
+type ViaTags<T> = HashMap<RecommenderNode<T>, HashMap<String, u32>>;
+
 /// A recommender that holds objects, tags and their relationship,
 /// and is able to return recommendations.
 pub struct Recommender<T> {
     graph: Graph<RecommenderNode<T>>,
+    edge_timestamps: HashMap<T, HashMap<String, u64>>,
+    insertion_order: HashMap<T, u64>,
+    next_insertion: u64,
+    blocklist: Option<Arc<RwLock<HashSet<T>>>>,
+    tag_weights: HashMap<String, f32>,
 }
 
 impl<T: Eq + Clone + Hash> Recommender<T> {
@@ -34,175 +297,160 @@ impl<T: Eq + Clone + Hash> Recommender<T> {
     pub fn new() -> Recommender<T> {
         Recommender {
             graph: Graph::new(),
+            edge_timestamps: HashMap::new(),
+            insertion_order: HashMap::new(),
+            next_insertion: 0,
+            blocklist: None,
+            tag_weights: HashMap::new(),
         }
     }
 
-    /// Adds an object to this recommender.
-    pub fn add_object(&mut self, object: &T) {
-        self.graph
-            .add_node(&RecommenderNode::Object(object.clone()));
-    }
-
-    /// Adds a tag to this recommender.
-    pub fn add_tag(&mut self, tag: &str) {
-        self.graph
-            .add_node(&RecommenderNode::Tag(String::from(tag)));
-    }
-
-    /// Assigns a tag to an object.
-    pub fn tag_object(&mut self, object: &T, tag: &str) {
-        self.graph.add_edge(
-            &RecommenderNode::Object(object.clone()),
-            &RecommenderNode::Tag(String::from(tag)),
-        );
-    }
-
-    fn recommendations_map(
-        &self,
-        from: &RecommenderNode<T>,
-        depth: u8,
-        max_total_steps: usize,
-        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
-    ) -> HashMap<RecommenderNode<T>, u32> {
-        let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
-        let mut steps_acc = 0;
-        while steps_acc < max_total_steps {
-            let walk = self.graph.random_walk(from, depth, &weight_fun);
-            if walk.len() == 0 {
-                return acc;
-            }
-            for visited in walk {
-                let count = acc.entry(visited).or_insert(0);
-                *count += 1;
-                steps_acc += 1;
-            }
+    /// Creates a new recommender from a list of `(object, tag)` edges,
+    /// assigning each tag to its object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// let recommender = Recommender::from_bipartite_edges(vec![
+    ///     (raid.clone(), String::from("Action")),
+    ///     (rocky.clone(), String::from("Action")),
+    /// ]);
+    ///
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn from_bipartite_edges(edges: impl IntoIterator<Item = (T, String)>) -> Recommender<T> {
+        let mut recommender = Recommender::new();
+        recommender.begin_batch();
+        for (object, tag) in edges {
+            recommender.tag_object(&object, &tag);
         }
-        acc
+        recommender.end_batch();
+        recommender
     }
 
-    /// Receives a set of queries (that can be either tags or objects) and
-    /// returns an ordered sequence of recommendations (with the first one
-    /// being the "best" one).
+    /// Sets a blocklist of objects that are never returned by
+    /// [`recommendations`](#method.recommendations),
+    /// [`object_recommendations`](#method.object_recommendations) and
+    /// every other operation that returns recommendations.
     ///
-    /// The resulting recommendations can be either objects or tags, so it
-    /// is advised to filter the result according to the expectations.
+    /// The blocklist is read on every call, so it can be updated through
+    /// the shared lock without modifying the recommender. Blocked objects
+    /// are still traversed by the walks.
     ///
     /// # Examples
     ///
     /// ```
     /// use pixie_rust::recommender::Recommender;
-    /// use pixie_rust::recommender::RecommenderNode;
+    /// use std::collections::HashSet;
+    /// use std::sync::Arc;
+    /// use std::sync::RwLock;
     ///
     /// let mut recommender: Recommender<String> = Recommender::new();
     ///
     /// let raid = String::from("The Raid");
     /// let rocky = String::from("Rocky");
-    /// let python = String::from("Monty Python and The Holy Grail");
     ///
-    /// let action = String::from("Action");
-    /// let comedy = String::from("Comedy");
-    /// let drama = String::from("Drama");
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
     ///
-    /// recommender.add_object(&raid);
-    /// recommender.add_object(&rocky);
-    /// recommender.add_object(&python);
+    /// let blocklist = Arc::new(RwLock::new(HashSet::new()));
+    /// recommender.set_blocklist(blocklist.clone());
     ///
-    /// recommender.add_tag(&action);
-    /// recommender.add_tag(&comedy);
-    /// recommender.add_tag(&drama);
+    /// blocklist.write().unwrap().insert(rocky);
     ///
-    /// recommender.tag_object(&raid, &action);
-    /// recommender.tag_object(&rocky, &action);
-    /// recommender.tag_object(&rocky, &drama);
-    /// recommender.tag_object(&python, &comedy);
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(recommendations.is_empty());
+    /// ```
+    pub fn set_blocklist(&mut self, blocklist: Arc<RwLock<HashSet<T>>>) {
+        self.blocklist = Some(blocklist);
+    }
+
+    /// Sets a multiplier of the weight of all the edges from an object to
+    /// `tag`, which is applied in addition to the weight functions of
+    /// each call. Tags without a multiplier have a multiplier of `1.0`.
     ///
-    /// let recommendations = recommender
-    ///     .recommendations(
-    ///         &vec![RecommenderNode::Tag(action)],
-    ///         10,
-    ///         10,
-    ///         |_, _| 1.0,
-    ///         |_, _| 1.0
-    ///     )
-    ///     .iter()
-    ///     .filter(|node| match node {
-    ///         RecommenderNode::Tag(_) => false,
-    ///         RecommenderNode::Object(_) => true
-    ///     })
-    ///     .cloned()
-    ///     .collect::<Vec<RecommenderNode<String>>>();
+    /// This can be used to down-weight generic tags (e.g. `"Movie"`)
+    /// without changing every weight function.
+    ///
+    /// # Examples
     ///
-    /// assert!(
-    ///     recommendations[0] == RecommenderNode::Object(rocky) ||
-    ///     recommendations[0] == RecommenderNode::Object(raid)
-    ///     )
     /// ```
-    pub fn recommendations(
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// recommender.set_tag_weight("Action", 0.0);
+    ///
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(recommendations.is_empty());
+    /// ```
+    pub fn set_tag_weight(&mut self, tag: &str, multiplier: f32) {
+        self.tag_weights.insert(String::from(tag), multiplier);
+    }
+
+    /// Weight of an edge during a walk, taking the tag multipliers into
+    /// account.
+    fn walk_weight(
         &self,
-        queries: &Vec<RecommenderNode<T>>,
-        depth: u8,
-        max_total_steps: usize,
+        from: &RecommenderNode<T>,
+        to: &RecommenderNode<T>,
         object_to_tag_weight: impl Fn(&T, &String) -> f32,
         tag_to_object_weight: impl Fn(&String, &T) -> f32,
-    ) -> Vec<RecommenderNode<T>> {
-        let query_scaling_factors = queries
-            .iter()
-            .map(|q| {
-                let degree = self.graph.degree(q) as f64;
-                degree * (self.graph.max_degree() as f64 - degree.log2())
-            })
-            .collect::<Vec<f64>>();
-
-        let total_scaling: f64 = query_scaling_factors.iter().sum();
-
-        let mut all_recommendations: HashMap<RecommenderNode<T>, f64> = HashMap::new();
-        for (q, s) in queries.iter().zip(query_scaling_factors.iter()) {
-            let max_steps: usize = ((max_total_steps as f64) * s / total_scaling) as usize;
-            let query_recommendations =
-                self.recommendations_map(q, depth, max_steps, |from, to| match (from, to) {
-                    (RecommenderNode::Tag(tag), RecommenderNode::Object(obj)) => {
-                        tag_to_object_weight(tag, obj)
-                    }
-                    (RecommenderNode::Object(obj), RecommenderNode::Tag(tag)) => {
-                        object_to_tag_weight(obj, tag)
-                    }
-                    _ => 0.0,
-                });
-            for (key, value) in query_recommendations.iter() {
-                let value_sqrt = (value.clone() as f64).sqrt();
-                all_recommendations
-                    .entry(key.clone())
-                    .and_modify(|x| *x += value_sqrt)
-                    .or_insert(value_sqrt);
-            }
+    ) -> f32 {
+        let weight = bipartite_weight(from, to, object_to_tag_weight, tag_to_object_weight);
+        match to {
+            RecommenderNode::Tag(tag) => match self.tag_weights.get(tag) {
+                Some(multiplier) => weight * multiplier,
+                None => weight,
+            },
+            RecommenderNode::Object(_) => weight,
         }
+    }
 
-        let mut queries_set: HashSet<&RecommenderNode<T>> = HashSet::new();
-        for q in queries {
-            queries_set.insert(q);
+    fn record_insertion(&mut self, object: &T) {
+        if !self.insertion_order.contains_key(object) {
+            self.insertion_order
+                .insert(object.clone(), self.next_insertion);
+            self.next_insertion += 1;
         }
+    }
 
-        let mut top_recommendations = all_recommendations
-            .iter()
-            .filter(|(k, _)| !queries_set.contains(*k))
-            .map(|(k, v)| (k, ((v * v) as u32)))
-            .collect::<Vec<(&RecommenderNode<T>, u32)>>();
-        top_recommendations.sort_by_key(|(_, v)| *v);
-        top_recommendations.reverse();
-        top_recommendations
-            .iter()
-            .map(|(k, _)| k)
+    fn insertion_rank(&self, object: &T) -> u64 {
+        self.insertion_order
+            .get(object)
             .cloned()
-            .cloned()
-            .collect()
+            .unwrap_or(u64::MAX)
     }
 
-    /// Receives a set of queries (that can only objects) and
-    /// returns an ordered sequence of recommendations (with the first one
-    /// being the "best" one).
-    ///
-    /// This is a simplified version of the `recommendations` operation
-    /// that only returns objects.
+    /// Adds an object to this recommender.
+    pub fn add_object(&mut self, object: &T) {
+        self.record_insertion(object);
+        self.graph
+            .add_node(&RecommenderNode::Object(object.clone()));
+    }
+
+    /// Adds a tag to this recommender.
+    pub fn add_tag(&mut self, tag: &str) {
+        self.graph
+            .add_node(&RecommenderNode::Tag(String::from(tag)));
+    }
+
+    /// Checks if an object is part of this recommender.
     ///
     /// # Examples
     ///
@@ -211,150 +459,5994 @@ impl<T: Eq + Clone + Hash> Recommender<T> {
     ///
     /// let mut recommender: Recommender<String> = Recommender::new();
     ///
-    /// let raid = String::from("The Raid");
-    /// let rocky = String::from("Rocky");
-    /// let python = String::from("Monty Python and The Holy Grail");
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// assert!(recommender.has_object(&String::from("The Raid")));
+    /// assert!(!recommender.has_object(&String::from("Action")));
+    /// ```
+    pub fn has_object(&self, object: &T) -> bool {
+        self.graph
+            .contains(&RecommenderNode::Object(object.clone()))
+    }
+
+    /// Checks if a tag is part of this recommender.
     ///
-    /// let action = String::from("Action");
-    /// let comedy = String::from("Comedy");
-    /// let drama = String::from("Drama");
+    /// # Examples
     ///
-    /// recommender.add_object(&raid);
-    /// recommender.add_object(&rocky);
-    /// recommender.add_object(&python);
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
     ///
-    /// recommender.add_tag(&action);
-    /// recommender.add_tag(&comedy);
-    /// recommender.add_tag(&drama);
+    /// let mut recommender: Recommender<String> = Recommender::new();
     ///
-    /// recommender.tag_object(&raid, &action);
-    /// recommender.tag_object(&rocky, &action);
-    /// recommender.tag_object(&rocky, &drama);
-    /// recommender.tag_object(&python, &comedy);
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// assert!(recommender.has_tag("Action"));
+    /// assert!(!recommender.has_tag("The Raid"));
+    /// ```
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.graph
+            .contains(&RecommenderNode::Tag(String::from(tag)))
+    }
+
+    /// Relates two objects directly (e.g. because they are often bought
+    /// together), creating them if needed.
     ///
-    /// let recommendations = recommender
-    ///     .object_recommendations(
-    ///         &vec![raid.clone()],
-    ///         50,
-    ///         50,
-    ///         |_, _| 1.0,
-    ///         |_, _| 1.0
-    ///     )
-    ///     .iter()
-    ///     .cloned()
-    ///     .collect::<Vec<String>>();
+    /// These edges are only traversed by
+    /// [`recommendations_with_object_edges`](#method.recommendations_with_object_edges).
+    /// Note that they make the graph no longer bipartite.
+    pub fn relate_objects(&mut self, object_a: &T, object_b: &T) {
+        self.record_insertion(object_a);
+        self.record_insertion(object_b);
+        self.graph.add_edge(
+            &RecommenderNode::Object(object_a.clone()),
+            &RecommenderNode::Object(object_b.clone()),
+        );
+    }
+
+    /// Lists all objects of this recommender, in no particular order.
+    ///
+    /// # Examples
     ///
-    /// assert!(recommendations.len() > 0);
-    /// assert!(recommendations[0] == rocky)
     /// ```
-    pub fn object_recommendations(
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.add_object(&String::from("Rocky"));
+    ///
+    /// let mut objects = recommender.objects().collect::<Vec<&String>>();
+    /// objects.sort();
+    /// assert_eq!(objects, vec!["Rocky", "The Raid"]);
+    /// ```
+    pub fn objects(&self) -> impl Iterator<Item = &T> {
+        self.graph.nodes().flat_map(|node| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj),
+        })
+    }
+
+    /// Lists all tags of this recommender, in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.add_tag("Comedy");
+    ///
+    /// let mut tags = recommender.tags().collect::<Vec<&str>>();
+    /// tags.sort();
+    /// assert_eq!(tags, vec!["Action", "Comedy"]);
+    /// ```
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.graph.nodes().flat_map(|node| match node {
+            RecommenderNode::Tag(tag) => Some(tag.as_str()),
+            RecommenderNode::Object(_) => None,
+        })
+    }
+
+    /// Assigns a tag to an object.
+    pub fn tag_object(&mut self, object: &T, tag: &str) {
+        self.record_insertion(object);
+        self.graph.add_edge(
+            &RecommenderNode::Object(object.clone()),
+            &RecommenderNode::Tag(String::from(tag)),
+        );
+    }
+
+    /// Assigns a tag to an object, recording when the relationship was
+    /// created.
+    ///
+    /// The `timestamp` can be in any unit (e.g. seconds since the epoch),
+    /// as long as it is consistent with the cutoff used in
+    /// [`recommendations_since`](#method.recommendations_since). Tagging the
+    /// same object again updates the timestamp. Edges created with
+    /// [`tag_object`](#method.tag_object) have timestamp `0`.
+    pub fn tag_object_at(&mut self, object: &T, tag: &str, timestamp: u64) {
+        self.tag_object(object, tag);
+        self.edge_timestamps
+            .entry(object.clone())
+            .or_default()
+            .insert(String::from(tag), timestamp);
+    }
+
+    /// Removes a tag from an object, if it was assigned.
+    ///
+    /// Both the object and the tag are kept in the recommender, even if
+    /// they are no longer connected to anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.untag_object(&rocky, "Action");
+    ///
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(recommendations.is_empty());
+    /// ```
+    pub fn untag_object(&mut self, object: &T, tag: &str) {
+        let removed = self.graph.remove_edge(
+            &RecommenderNode::Object(object.clone()),
+            &RecommenderNode::Tag(String::from(tag)),
+        );
+        if removed {
+            if let Some(tags) = self.edge_timestamps.get_mut(object) {
+                tags.remove(tag);
+                if tags.is_empty() {
+                    self.edge_timestamps.remove(object);
+                }
+            }
+        }
+    }
+
+    /// Removes an object and all its edges from this recommender.
+    ///
+    /// The tags of the object are kept, even if they are no longer
+    /// assigned to any object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.remove_object(&rocky);
+    ///
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(recommendations.is_empty());
+    /// ```
+    pub fn remove_object(&mut self, object: &T) {
+        self.graph
+            .remove_node(&RecommenderNode::Object(object.clone()));
+        self.edge_timestamps.remove(object);
+        self.insertion_order.remove(object);
+    }
+
+    /// Removes a tag from this recommender, detaching it from all its
+    /// objects.
+    ///
+    /// The objects are kept, even if they no longer have any tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.remove_tag("Action");
+    ///
+    /// let recommendations =
+    ///     recommender.object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(recommendations.is_empty());
+    /// assert_eq!(recommender.objects().count(), 2);
+    /// ```
+    pub fn remove_tag(&mut self, tag: &str) {
+        let tag_node = RecommenderNode::Tag(String::from(tag));
+        for object in self.graph.successors(&tag_node) {
+            if let RecommenderNode::Object(obj) = object {
+                if let Some(tags) = self.edge_timestamps.get_mut(&obj) {
+                    tags.remove(tag);
+                    if tags.is_empty() {
+                        self.edge_timestamps.remove(&obj);
+                    }
+                }
+            }
+        }
+        self.graph.remove_node(&tag_node);
+        self.tag_weights.remove(tag);
+    }
+
+    fn edge_timestamp(&self, object: &T, tag: &str) -> u64 {
+        self.edge_timestamps
+            .get(object)
+            .and_then(|tags| tags.get(tag))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Suggests tags for an object, based on the tags of similar objects.
+    ///
+    /// Returns up to `limit` tags, that are not yet assigned to the object,
+    /// paired with their score, ordered from the best to the worst.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    ///
+    /// let suggestions = recommender.suggest_tags(&raid, 5, 10, 100, |_, _| 1.0, |_, _| 1.0);
+    ///
+    /// assert_eq!(suggestions.len(), 1);
+    /// assert_eq!(suggestions[0].0, "Drama");
+    /// ```
+    pub fn suggest_tags(
         &self,
-        queries: &Vec<T>,
+        object: &T,
+        limit: usize,
         depth: u8,
         max_total_steps: usize,
         object_to_tag_weight: impl Fn(&T, &String) -> f32,
         tag_to_object_weight: impl Fn(&String, &T) -> f32,
-    ) -> Vec<T> {
-        let node_queries: Vec<RecommenderNode<T>> = queries
-            .iter()
-            .map(|x| RecommenderNode::Object(x.clone()))
+    ) -> Vec<(String, f64)> {
+        let object_node = RecommenderNode::Object(object.clone());
+        let assigned_tags = self.graph.successors(&object_node);
+        let all_recommendations: HashMap<RecommenderNode<T>, f64> = self
+            .recommendations_map(&object_node, depth, max_total_steps, |from, to| {
+                self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+            })
+            .into_iter()
+            .map(|(k, v)| (k, (v as f64).sqrt()))
             .collect();
-        self.recommendations(
-            &node_queries,
+
+        self.rank_scored_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => !assigned_tags.contains(node),
+            RecommenderNode::Object(_) => false,
+        })
+        .into_iter()
+        .take(limit)
+        .flat_map(|(node, score)| match node {
+            RecommenderNode::Tag(tag) => Some((tag, score)),
+            RecommenderNode::Object(_) => None,
+        })
+        .collect()
+    }
+
+    /// Creates a new recommender with only the given tags, the objects
+    /// tagged with them and the edges between those.
+    ///
+    /// Tags that are not part of this recommender are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let action_recommender = recommender.clone_subset(&[String::from("Action")]);
+    ///
+    /// let recommendations = action_recommender.object_recommendations(
+    ///     &[raid],
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn clone_subset(&self, tags: &[String]) -> Recommender<T> {
+        let mut subset = Recommender::new();
+        for tag in tags {
+            let tag_node = RecommenderNode::Tag(tag.clone());
+            if !self.graph.contains(&tag_node) {
+                continue;
+            }
+            subset.graph.add_node(&tag_node);
+            for node in self.graph.successors(&tag_node) {
+                subset.graph.add_edge(&node, &tag_node);
+                if let RecommenderNode::Object(obj) = &node {
+                    if let Some(insertion) = self.insertion_order.get(obj) {
+                        subset.insertion_order.insert(obj.clone(), *insertion);
+                    }
+                    let timestamp = self.edge_timestamp(obj, tag);
+                    if timestamp > 0 {
+                        subset
+                            .edge_timestamps
+                            .entry(obj.clone())
+                            .or_default()
+                            .insert(tag.clone(), timestamp);
+                    }
+                }
+            }
+        }
+        subset.next_insertion = self.next_insertion;
+        subset.blocklist = self.blocklist.clone();
+        subset.tag_weights = self.tag_weights.clone();
+        subset
+    }
+
+    /// Starts a batch of edits, during which some cached information is
+    /// not updated. See [`Graph::begin_batch`].
+    ///
+    /// [`Graph::begin_batch`]: graph/struct.Graph.html#method.begin_batch
+    pub fn begin_batch(&mut self) {
+        self.graph.begin_batch();
+    }
+
+    /// Ends a batch of edits, updating the cached information.
+    pub fn end_batch(&mut self) {
+        self.graph.end_batch();
+    }
+
+    /// Counts, for each pair of tags, how many objects are tagged with both.
+    ///
+    /// Each pair is returned only once, with the tags in lexicographical
+    /// order. Pairs of tags that never co-occur are not returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Drama");
+    ///
+    /// let cooccurrence = recommender.tag_cooccurrence();
+    ///
+    /// assert_eq!(cooccurrence.len(), 1);
+    /// assert_eq!(cooccurrence[&(String::from("Action"), String::from("Drama"))], 1);
+    /// ```
+    pub fn tag_cooccurrence(&self) -> HashMap<(String, String), u32> {
+        let mut cooccurrence: HashMap<(String, String), u32> = HashMap::new();
+        for node in self.graph.nodes() {
+            if let RecommenderNode::Object(_) = node {
+                let mut tags = self
+                    .graph
+                    .successors(node)
+                    .into_iter()
+                    .flat_map(|succ| match succ {
+                        RecommenderNode::Tag(tag) => Some(tag),
+                        RecommenderNode::Object(_) => None,
+                    })
+                    .collect::<Vec<String>>();
+                tags.sort();
+                for (i, tag_a) in tags.iter().enumerate() {
+                    for tag_b in tags[i + 1..].iter() {
+                        *cooccurrence
+                            .entry((tag_a.clone(), tag_b.clone()))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        cooccurrence
+    }
+
+    /// Removes all objects without tags and all tags without objects,
+    /// returning how many were removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.add_object(&String::from("Rocky"));
+    /// recommender.add_tag("Comedy");
+    ///
+    /// assert_eq!(recommender.remove_isolated(), 2);
+    /// assert!(recommender.validate().is_valid());
+    /// ```
+    pub fn remove_isolated(&mut self) -> usize {
+        let removed = self.graph.remove_isolated();
+        let graph = &self.graph;
+        self.edge_timestamps
+            .retain(|obj, _| graph.contains(&RecommenderNode::Object(obj.clone())));
+        self.insertion_order
+            .retain(|obj, _| graph.contains(&RecommenderNode::Object(obj.clone())));
+        removed
+    }
+
+    /// Returns the number of objects and tags that the recommender can hold
+    /// without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.graph.capacity()
+    }
+
+    /// Shrinks the memory used by the recommender as much as possible.
+    ///
+    /// This is useful for long lived recommenders, after removing a large
+    /// number of objects, tags or their relationships.
+    pub fn shrink_to_fit(&mut self) {
+        self.graph.shrink_to_fit();
+        self.edge_timestamps.shrink_to_fit();
+        for tags in self.edge_timestamps.values_mut() {
+            tags.shrink_to_fit();
+        }
+        self.insertion_order.shrink_to_fit();
+        self.tag_weights.shrink_to_fit();
+    }
+
+    /// Recomputes all cached information about the recommender from
+    /// scratch.
+    ///
+    /// This should not be needed during normal operation, but it can be
+    /// used after bulk edits to guarantee that no derived state is stale.
+    pub fn rebuild_index(&mut self) {
+        self.graph.rebuild_index();
+    }
+
+    fn accumulate_walks(
+        max_total_steps: usize,
+        mut walk: impl FnMut() -> LinkedList<RecommenderNode<T>>,
+        mut record: impl FnMut(LinkedList<RecommenderNode<T>>),
+    ) {
+        let mut steps_acc = 0;
+        while steps_acc < max_total_steps {
+            let visits = walk();
+            if visits.is_empty() {
+                return;
+            }
+            steps_acc += visits.len();
+            record(visits);
+        }
+    }
+
+    fn recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> HashMap<RecommenderNode<T>, u32> {
+        self.recommendations_map_with_mode(
+            from,
             depth,
             max_total_steps,
-            object_to_tag_weight,
-            tag_to_object_weight,
+            weight_fun,
+            AccumulationMode::All,
         )
-        .iter()
-        .flat_map(|node| match node {
-            RecommenderNode::Tag(_) => None,
-            RecommenderNode::Object(obj) => Some(obj.clone()),
-        })
-        .collect()
     }
-}
 
-impl<T: Eq + Hash + fmt::Debug> fmt::Debug for Recommender<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Recommender [{:?}]", self.graph)
+    fn recommendations_map_with_mode(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+        mode: AccumulationMode,
+    ) -> HashMap<RecommenderNode<T>, u32> {
+        let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+        self.fill_recommendations_map(from, depth, max_total_steps, weight_fun, mode, &mut acc);
+        acc
+    }
+
+    fn fill_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+        mode: AccumulationMode,
+        acc: &mut HashMap<RecommenderNode<T>, u32>,
+    ) {
+        acc.clear();
+        let mut rng = self.graph.new_rng();
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || {
+                self.graph
+                    .random_walk_with_rng(from, depth, &weight_fun, &mut rng)
+            },
+            |visits| {
+                for visited in visits {
+                    let is_object = matches!(visited, RecommenderNode::Object(_));
+                    if !(is_object && mode == AccumulationMode::TagsOnly) {
+                        *acc.entry(visited).or_insert(0) += 1;
+                    }
+                }
+            },
+        );
+    }
+
+    /// Counts the visits of the walks from a single query, as done by
+    /// [`recommendations`](#method.recommendations), into the given buffer.
+    ///
+    /// The buffer is cleared before being filled, so the same buffer can be
+    /// reused across many queries, without allocating a new map each time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let mut buffer = HashMap::new();
+    /// recommender.visit_counts_into(
+    ///     &RecommenderNode::Object(raid.clone()),
+    ///     3,
+    ///     30,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &mut buffer
+    /// );
+    ///
+    /// assert_eq!(buffer.values().sum::<u32>(), 30);
+    /// assert_eq!(buffer[&RecommenderNode::Tag(String::from("Action"))], 10);
+    /// ```
+    pub fn visit_counts_into(
+        &self,
+        query: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        buffer: &mut HashMap<RecommenderNode<T>, u32>,
+    ) {
+        self.fill_recommendations_map(
+            query,
+            depth,
+            max_total_steps,
+            |from, to| self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight),
+            AccumulationMode::All,
+            buffer,
+        );
+    }
+
+    /// Samples `n_walks` example walks from a query, with the same weights
+    /// that the recommendations would use.
+    ///
+    /// Each walk contains the visited nodes in order, starting at the
+    /// query, and has at most `depth` nodes. This is meant to debug weight
+    /// functions, as the walks show which edges are actually taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let query = RecommenderNode::Object(raid);
+    /// let walks = recommender.sample_walk_preview(&query, 3, 2, |_, _| 1.0, |_, _| 1.0);
+    ///
+    /// assert_eq!(walks.len(), 2);
+    /// for walk in walks {
+    ///     assert_eq!(walk[0], query);
+    ///     assert_eq!(walk[1], RecommenderNode::Tag(String::from("Action")));
+    /// }
+    /// ```
+    pub fn sample_walk_preview(
+        &self,
+        query: &RecommenderNode<T>,
+        depth: u8,
+        n_walks: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<Vec<RecommenderNode<T>>> {
+        let mut walks = self.graph.random_walks(query, n_walks, depth, |from, to| {
+            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+        });
+        for walk in walks.iter_mut() {
+            walk.reverse();
+        }
+        walks
+    }
+
+    fn early_stopping_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+        config: &EarlyStopping,
+    ) -> (HashMap<RecommenderNode<T>, u32>, usize) {
+        self.early_stopping_recommendations_map_with(
+            from,
+            depth,
+            max_total_steps,
+            weight_fun,
+            config,
+            |_| true,
+        )
+    }
+
+    /// Same as `early_stopping_recommendations_map`, but only the nodes
+    /// that satisfy `is_candidate` count towards the stopping condition.
+    fn early_stopping_recommendations_map_with(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+        config: &EarlyStopping,
+        is_candidate: impl Fn(&RecommenderNode<T>) -> bool,
+    ) -> (HashMap<RecommenderNode<T>, u32>, usize) {
+        let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+        let mut schedule = WalkSchedule::new(depth, config);
+        let mut candidates = 0;
+        let mut steps_acc = 0;
+        while steps_acc < max_total_steps && candidates < config.min_candidates() {
+            let visits = self
+                .graph
+                .random_walk(from, schedule.walk_length(), &weight_fun);
+            if visits.is_empty() {
+                break;
+            }
+            steps_acc += visits.len();
+            let mut new_candidates = 0;
+            for visited in visits {
+                let is_candidate = visited != *from && is_candidate(&visited);
+                let count = acc.entry(visited).or_insert(0);
+                *count += 1;
+                if *count == config.min_visits() && is_candidate {
+                    new_candidates += 1;
+                }
+            }
+            candidates += new_candidates;
+            schedule.record(new_candidates);
+        }
+        (acc, steps_acc)
+    }
+
+    fn record_tagged_walk(
+        acc: &mut HashMap<RecommenderNode<T>, TaggedVisits>,
+        visits: LinkedList<RecommenderNode<T>>,
+    ) {
+        let mut previous_tag: Option<String> = None;
+        for visited in visits.into_iter().rev() {
+            let current_tag = match &visited {
+                RecommenderNode::Tag(tag) => Some(tag.clone()),
+                RecommenderNode::Object(_) => None,
+            };
+            let entry = acc.entry(visited).or_default();
+            entry.visits += 1;
+            if let (None, Some(tag)) = (&current_tag, previous_tag) {
+                *entry.via_tags.entry(tag).or_insert(0) += 1;
+            }
+            previous_tag = current_tag;
+        }
+    }
+
+    fn tagged_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> HashMap<RecommenderNode<T>, TaggedVisits> {
+        let mut acc: HashMap<RecommenderNode<T>, TaggedVisits> = HashMap::new();
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || self.graph.random_walk(from, depth, &weight_fun),
+            |visits| Recommender::record_tagged_walk(&mut acc, visits),
+        );
+        acc
+    }
+
+    /// Aggregates the visits of the walks from each object query, along
+    /// with the tags that were visited right before each node.
+    fn tagged_aggregated_recommendations(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> (HashMap<RecommenderNode<T>, f64>, ViaTags<T>) {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        let query_budgets = self.query_budgets(&node_queries, max_total_steps);
+
+        let mut all_recommendations: HashMap<RecommenderNode<T>, f64> = HashMap::new();
+        let mut via_tags: ViaTags<T> = HashMap::new();
+        for (q, max_steps) in node_queries.iter().zip(query_budgets) {
+            let tagged_visits = self.tagged_recommendations_map(q, depth, max_steps, |from, to| {
+                self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+            });
+            for (node, visits) in tagged_visits {
+                *all_recommendations.entry(node.clone()).or_insert(0.0) +=
+                    (visits.visits as f64).sqrt();
+                let node_tags = via_tags.entry(node).or_default();
+                for (tag, count) in visits.via_tags {
+                    *node_tags.entry(tag).or_insert(0) += count;
+                }
+            }
+        }
+        (all_recommendations, via_tags)
+    }
+
+    fn tag_boosted_counts(
+        tagged_visits: &HashMap<RecommenderNode<T>, TaggedVisits>,
+        shared_tag_boost: f64,
+    ) -> HashMap<RecommenderNode<T>, f64> {
+        tagged_visits
+            .iter()
+            .map(|(node, visits)| {
+                let extra_tags = visits.via_tags.len().saturating_sub(1) as f64;
+                let boost = (1.0 + shared_tag_boost * extra_tags).max(0.0);
+                (node.clone(), visits.visits as f64 * boost)
+            })
+            .collect()
+    }
+
+    fn approximate_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+        config: &SketchConfig,
+    ) -> HashMap<RecommenderNode<T>, u32> {
+        let mut acc: TopKCounter<RecommenderNode<T>> = TopKCounter::new(config);
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || self.graph.random_walk(from, depth, &weight_fun),
+            |visits| {
+                for visited in visits {
+                    acc.increment(visited);
+                }
+            },
+        );
+        acc.into_counts()
+    }
+
+    /// Checks if an object is in the blocklist. The blocklist is read once,
+    /// when this method is called, and the lock is held until the returned
+    /// function is dropped.
+    fn blocked_objects(&self) -> impl Fn(&T) -> bool + '_ {
+        let blocklist = self.blocklist.as_ref().map(|blocklist| {
+            // The blocklist is only read, so a poisoned lock can't leave
+            // it in an inconsistent state.
+            blocklist
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        });
+        move |obj| {
+            blocklist
+                .as_ref()
+                .is_some_and(|blocklist| blocklist.contains(obj))
+        }
+    }
+
+    fn rank_recommendations(
+        &self,
+        all_recommendations: &HashMap<RecommenderNode<T>, f64>,
+        keep: impl Fn(&RecommenderNode<T>) -> bool,
+    ) -> Vec<RecommenderNode<T>> {
+        self.rank_scored_recommendations(all_recommendations, keep)
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    fn rank_scored_recommendations(
+        &self,
+        all_recommendations: &HashMap<RecommenderNode<T>, f64>,
+        keep: impl Fn(&RecommenderNode<T>) -> bool,
+    ) -> Vec<(RecommenderNode<T>, f64)> {
+        self.rank_ordered_recommendations(all_recommendations.iter().map(|(k, v)| (k, v * v)), keep)
+    }
+
+    /// Same as `rank_scored_recommendations`, but the scores are final
+    /// (i.e. they are not squared) and ties are ranked according to the
+    /// order of `scores`.
+    ///
+    /// Every ranking goes through this method, so the objects in the
+    /// blocklist are never kept.
+    fn rank_ordered_recommendations<'b>(
+        &self,
+        scores: impl Iterator<Item = (&'b RecommenderNode<T>, f64)>,
+        keep: impl Fn(&RecommenderNode<T>) -> bool,
+    ) -> Vec<(RecommenderNode<T>, f64)>
+    where
+        T: 'b,
+    {
+        let is_blocked = self.blocked_objects();
+        Recommender::sort_recommendations(scores, |node| {
+            keep(node) && !node.as_object().is_some_and(&is_blocked)
+        })
+    }
+
+    /// Sorts the recommendations that are kept by descending score. The
+    /// sort is stable, so ties keep the order of `scores`.
+    fn sort_recommendations<'b>(
+        scores: impl Iterator<Item = (&'b RecommenderNode<T>, f64)>,
+        keep: impl Fn(&RecommenderNode<T>) -> bool,
+    ) -> Vec<(RecommenderNode<T>, f64)>
+    where
+        T: 'b,
+    {
+        let mut top_recommendations = scores
+            .filter(|(k, _)| keep(k))
+            .collect::<Vec<(&RecommenderNode<T>, f64)>>();
+        top_recommendations.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        top_recommendations
+            .into_iter()
+            .map(|(k, v)| (k.clone(), v))
+            .collect()
+    }
+
+    fn query_budgets(&self, queries: &[RecommenderNode<T>], max_total_steps: usize) -> Vec<usize> {
+        self.degree_budgets(
+            queries.iter().map(|q| self.graph.degree(q)),
+            max_total_steps,
+        )
+    }
+
+    fn degree_budgets(
+        &self,
+        degrees: impl Iterator<Item = usize>,
+        max_total_steps: usize,
+    ) -> Vec<usize> {
+        let max_degree = self.graph.max_degree() as f64;
+        let query_scaling_factors = degrees
+            .map(|degree| Recommender::<T>::query_scaling_factor(degree, max_degree))
+            .collect::<Vec<f64>>();
+        Recommender::<T>::split_steps(&query_scaling_factors, max_total_steps)
+    }
+
+    /// Same as `query_budgets`, but the scaling factor of each query is
+    /// multiplied by its weight. Queries with a weight <= 0 (or without
+    /// edges) get no steps.
+    fn weighted_query_budgets(
+        &self,
+        queries: &[(RecommenderNode<T>, f32)],
+        max_total_steps: usize,
+    ) -> Vec<usize> {
+        let max_degree = self.graph.max_degree() as f64;
+        let query_scaling_factors = queries
+            .iter()
+            .map(|(q, weight)| {
+                let degree = self.graph.degree(q);
+                if *weight > 0.0 {
+                    Recommender::<T>::query_scaling_factor(degree, max_degree) * f64::from(*weight)
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<f64>>();
+        Recommender::<T>::split_steps(&query_scaling_factors, max_total_steps)
+    }
+
+    /// Queries without edges (or unknown) can't be walked, so they get a
+    /// factor of `0`, instead of `0 * (max_degree - log2(0))`, which is NaN.
+    fn query_scaling_factor(degree: usize, max_degree: f64) -> f64 {
+        if degree == 0 {
+            return 0.0;
+        }
+        let degree = degree as f64;
+        degree * (max_degree - degree.log2())
+    }
+
+    /// Splits the steps proportionally to the scaling factors. If no query
+    /// has a positive factor, no query gets steps.
+    fn split_steps(query_scaling_factors: &[f64], max_total_steps: usize) -> Vec<usize> {
+        let total_scaling: f64 = query_scaling_factors.iter().sum();
+        if !(total_scaling.is_finite() && total_scaling > 0.0) {
+            return vec![0; query_scaling_factors.len()];
+        }
+
+        query_scaling_factors
+            .iter()
+            .map(|s| ((max_total_steps as f64) * s / total_scaling) as usize)
+            .collect()
+    }
+
+    fn aggregated_recommendations<V: Copy + Into<f64>>(
+        &self,
+        queries: &[RecommenderNode<T>],
+        max_total_steps: usize,
+        query_counts: impl Fn(usize, &RecommenderNode<T>, usize) -> HashMap<RecommenderNode<T>, V>,
+    ) -> HashMap<RecommenderNode<T>, f64> {
+        self.covered_recommendations(
+            queries,
+            self.query_budgets(queries, max_total_steps),
+            query_counts,
+        )
+        .into_iter()
+        .map(|(k, (score, _))| (k, score))
+        .collect()
+    }
+
+    /// Same as `aggregated_recommendations`, but also returns how many
+    /// queries visited each node.
+    fn covered_recommendations<V: Copy + Into<f64>>(
+        &self,
+        queries: &[RecommenderNode<T>],
+        query_budgets: Vec<usize>,
+        query_counts: impl Fn(usize, &RecommenderNode<T>, usize) -> HashMap<RecommenderNode<T>, V>,
+    ) -> HashMap<RecommenderNode<T>, (f64, usize)> {
+        let mut all_recommendations: HashMap<RecommenderNode<T>, (f64, usize)> = HashMap::new();
+        for (i, (q, max_steps)) in queries.iter().zip(query_budgets).enumerate() {
+            let query_recommendations = query_counts(i, q, max_steps);
+            for (key, value) in query_recommendations.iter() {
+                let value_sqrt = (*value).into().sqrt();
+                let entry = all_recommendations.entry(key.clone()).or_insert((0.0, 0));
+                entry.0 += value_sqrt;
+                entry.1 += 1;
+            }
+        }
+        all_recommendations
+    }
+
+    /// Receives a set of queries (that can be either tags or objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one).
+    ///
+    /// The resulting recommendations can be either objects or tags, so it
+    /// is advised to filter the result according to the expectations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// let action = String::from("Action");
+    /// let comedy = String::from("Comedy");
+    /// let drama = String::from("Drama");
+    ///
+    /// recommender.add_object(&raid);
+    /// recommender.add_object(&rocky);
+    /// recommender.add_object(&python);
+    ///
+    /// recommender.add_tag(&action);
+    /// recommender.add_tag(&comedy);
+    /// recommender.add_tag(&drama);
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    /// recommender.tag_object(&rocky, &drama);
+    /// recommender.tag_object(&python, &comedy);
+    ///
+    /// let recommendations = recommender
+    ///     .recommendations(
+    ///         &vec![RecommenderNode::Tag(action)],
+    ///         10,
+    ///         10,
+    ///         |_, _| 1.0,
+    ///         |_, _| 1.0
+    ///     )
+    ///     .iter()
+    ///     .filter(|node| match node {
+    ///         RecommenderNode::Tag(_) => false,
+    ///         RecommenderNode::Object(_) => true
+    ///     })
+    ///     .cloned()
+    ///     .collect::<Vec<RecommenderNode<String>>>();
+    ///
+    /// assert!(
+    ///     recommendations[0] == RecommenderNode::Object(rocky) ||
+    ///     recommendations[0] == RecommenderNode::Object(raid)
+    ///     )
+    /// ```
+    pub fn recommendations(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        self.recommendations_scored(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        )
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect()
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but each
+    /// node is returned along with its score.
+    ///
+    /// The score of a node is the square of the sum of the square roots
+    /// of its visit counts on each query, so, for a single query, it's the
+    /// number of visits. The nodes are sorted by descending score.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_scored(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     30,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// let (best, score) = &recommendations[0];
+    /// assert_eq!(*best, RecommenderNode::Tag(String::from("Action")));
+    /// assert!((score - 10.0).abs() < 1e-9);
+    /// ```
+    pub fn recommendations_scored(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<(RecommenderNode<T>, f64)> {
+        self.scored_recommendations_with(queries, depth, max_total_steps, |from, to| {
+            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+        })
+    }
+
+    /// Receives a set of queries (that can be either tags or objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one), where the walks can also follow the edges
+    /// between objects created with
+    /// [`relate_objects`](#method.relate_objects).
+    ///
+    /// The other operations never traverse those edges, which is the same
+    /// as using an `object_to_object_weight` of `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    /// recommender.relate_objects(&raid, &rocky);
+    ///
+    /// let recommendations = recommender.recommendations_with_object_edges(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert!(!recommendations.contains(&RecommenderNode::Object(python)));
+    /// ```
+    pub fn recommendations_with_object_edges(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        object_to_object_weight: impl Fn(&T, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        self.scored_recommendations_with(queries, depth, max_total_steps, |from, to| {
+            match (from, to) {
+                (RecommenderNode::Object(a), RecommenderNode::Object(b)) => {
+                    object_to_object_weight(a, b)
+                }
+                _ => self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight),
+            }
+        })
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect()
+    }
+
+    fn scored_recommendations_with(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> Vec<(RecommenderNode<T>, f64)> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, &weight_fun)
+            });
+
+        let mut queries_set: HashSet<&RecommenderNode<T>> = HashSet::new();
+        for q in queries {
+            queries_set.insert(q);
+        }
+
+        self.rank_scored_recommendations(&all_recommendations, |node| !queries_set.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but objects
+    /// and tags with the same score are ordered according to `tie_break`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::TieBreak;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// // Every walk goes from The Raid to Action and then to Rocky
+    /// let recommendations = recommender.recommendations_with_tie_break(
+    ///     &[RecommenderNode::Object(raid.clone())],
+    ///     3,
+    ///     30,
+    ///     |_, _| 1.0,
+    ///     |_, obj| if *obj == raid { 0.0 } else { 1.0 },
+    ///     TieBreak::ObjectsFirst
+    /// );
+    ///
+    /// assert_eq!(recommendations[0], RecommenderNode::Object(rocky));
+    /// ```
+    pub fn recommendations_with_tie_break(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        tie_break: TieBreak,
+    ) -> Vec<RecommenderNode<T>> {
+        let mut scored = self.recommendations_scored(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        );
+        tie_break.sort(&mut scored);
+        scored.into_iter().map(|(node, _)| node).collect()
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but queries
+    /// that are not part of the recommender are handled according to the
+    /// given `policy`.
+    ///
+    /// Unknown queries don't contribute to the recommendations, but they
+    /// would still take part in the split of the steps among the queries,
+    /// so they are always removed before walking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::UnknownQueryPolicy;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let typo = String::from("The Rad");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let queries = [RecommenderNode::Object(raid), RecommenderNode::Object(typo.clone())];
+    ///
+    /// let recommendations = recommender.recommendations_with_unknown_queries(
+    ///     &queries,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     UnknownQueryPolicy::Skip
+    /// );
+    /// assert!(recommendations.unwrap().contains(&RecommenderNode::Object(rocky)));
+    ///
+    /// let recommendations = recommender.recommendations_with_unknown_queries(
+    ///     &queries,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     UnknownQueryPolicy::Error
+    /// );
+    /// assert_eq!(recommendations.unwrap_err().queries, vec![RecommenderNode::Object(typo)]);
+    /// ```
+    pub fn recommendations_with_unknown_queries(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        policy: UnknownQueryPolicy,
+    ) -> Result<Vec<RecommenderNode<T>>, UnknownQuery<T>> {
+        let (known, unknown): (Vec<RecommenderNode<T>>, Vec<RecommenderNode<T>>) = queries
+            .iter()
+            .cloned()
+            .partition(|q| self.graph.contains(q));
+        if !unknown.is_empty() {
+            if policy == UnknownQueryPolicy::Error {
+                return Err(UnknownQuery { queries: unknown });
+            }
+            log::warn!("Skipping {} unknown queries", unknown.len());
+        }
+        Ok(self.recommendations(
+            &known,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        ))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but fails
+    /// with an [`UnknownQuery`](struct.UnknownQuery.html) error if some
+    /// queries are not part of the recommender, instead of returning no
+    /// recommendations for them.
+    ///
+    /// Queries that are part of the recommender but have no edges are not
+    /// errors: they don't contribute to the recommendations, and the other
+    /// queries get all the steps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.try_recommendations(
+    ///     &[RecommenderNode::Object(raid.clone())],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    /// assert!(recommendations.unwrap().contains(&RecommenderNode::Object(rocky)));
+    ///
+    /// let typo = RecommenderNode::Tag(String::from("Acton"));
+    /// let recommendations = recommender.try_recommendations(
+    ///     &[RecommenderNode::Object(raid), typo.clone()],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    /// assert_eq!(recommendations.unwrap_err().queries, vec![typo]);
+    /// ```
+    pub fn try_recommendations(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Result<Vec<RecommenderNode<T>>, UnknownQuery<T>> {
+        self.recommendations_with_unknown_queries(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+            UnknownQueryPolicy::Error,
+        )
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but only the
+    /// edges created at or after `cutoff` are traversed.
+    ///
+    /// Older edges are treated as if they had weight `0`. See
+    /// [`tag_object_at`](#method.tag_object_at).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let casablanca = String::from("Casablanca");
+    ///
+    /// recommender.tag_object_at(&raid, "Action", 100);
+    /// recommender.tag_object_at(&rocky, "Action", 200);
+    /// recommender.tag_object_at(&casablanca, "Action", 10);
+    ///
+    /// let recommendations = recommender.recommendations_since(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     50,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert!(!recommendations.contains(&RecommenderNode::Object(casablanca)));
+    /// ```
+    pub fn recommendations_since(
+        &self,
+        queries: &[RecommenderNode<T>],
+        cutoff: u64,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        self.recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            |obj, tag| {
+                if self.edge_timestamp(obj, tag) >= cutoff {
+                    object_to_tag_weight(obj, tag)
+                } else {
+                    0.0
+                }
+            },
+            |tag, obj| {
+                if self.edge_timestamp(obj, tag) >= cutoff {
+                    tag_to_object_weight(tag, obj)
+                } else {
+                    0.0
+                }
+            },
+        )
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the weight
+    /// functions also receive the query that started the walk.
+    ///
+    /// This allows weights that are relative to each query (e.g. the
+    /// affinity between the query and the next object).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&creed, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_with_query_weights(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _, _| 1.0,
+    ///     |query, _, obj| {
+    ///         let from_raid = *query == RecommenderNode::Object(String::from("The Raid"));
+    ///         if from_raid && obj == "Creed" { 0.0 } else { 1.0 }
+    ///     }
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert!(!recommendations.contains(&RecommenderNode::Object(creed)));
+    /// ```
+    pub fn recommendations_with_query_weights(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&RecommenderNode<T>, &T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&RecommenderNode<T>, &String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(
+                        from,
+                        to,
+                        |obj, tag| object_to_tag_weight(q, obj, tag),
+                        |tag, obj| tag_to_object_weight(q, tag, obj),
+                    )
+                })
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but each query
+    /// uses its own pair of weight functions.
+    ///
+    /// This is useful when mixing different kinds of queries (e.g. an
+    /// object seed that should respect the ratings and a tag seed that
+    /// should be uniform).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::WeightPair;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let recommendations = recommender.recommendations_per_query(
+    ///     &[
+    ///         (RecommenderNode::Object(raid), WeightPair::new(|_, _| 1.0, |_, _| 1.0)),
+    ///         (RecommenderNode::Tag(String::from("Comedy")), WeightPair::uniform()),
+    ///     ],
+    ///     10,
+    ///     100,
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert!(recommendations.contains(&RecommenderNode::Object(python)));
+    /// ```
+    pub fn recommendations_per_query(
+        &self,
+        queries: &[(RecommenderNode<T>, WeightPair<T>)],
+        depth: u8,
+        max_total_steps: usize,
+    ) -> Vec<RecommenderNode<T>> {
+        let query_nodes = queries
+            .iter()
+            .map(|(q, _)| q.clone())
+            .collect::<Vec<RecommenderNode<T>>>();
+
+        let all_recommendations =
+            self.aggregated_recommendations(&query_nodes, max_total_steps, |i, q, steps| {
+                let weights = &queries[i].1;
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &weights.object_to_tag, &weights.tag_to_object)
+                })
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !query_nodes.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but only nodes
+    /// that were reached by at least `min_query_coverage` of the queries
+    /// are returned.
+    ///
+    /// This prevents the results from being dominated by candidates that
+    /// are only related to a single query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    /// recommender.tag_object(&creed, "Comedy");
+    ///
+    /// let recommendations = recommender.recommendations_with_min_coverage(
+    ///     &[RecommenderNode::Object(raid), RecommenderNode::Object(creed)],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     2
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![RecommenderNode::Object(rocky)]);
+    /// ```
+    pub fn recommendations_with_min_coverage(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        min_query_coverage: usize,
+    ) -> Vec<RecommenderNode<T>> {
+        let covered_recommendations = self.covered_recommendations(
+            queries,
+            self.query_budgets(queries, max_total_steps),
+            |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            },
+        );
+        let all_recommendations: HashMap<RecommenderNode<T>, f64> = covered_recommendations
+            .into_iter()
+            .filter(|(_, (_, coverage))| *coverage >= min_query_coverage)
+            .map(|(k, (score, _))| (k, score))
+            .collect();
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the first
+    /// hop of the walks that start from tag queries is picked according to
+    /// the `first_hop_strategy`.
+    ///
+    /// This can be used to anchor the walks of broad tags on their most
+    /// relevant objects. Object queries are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::FirstHopStrategy;
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    ///
+    /// let recommendations = recommender.recommendations_with_first_hop(
+    ///     &[RecommenderNode::Tag(String::from("Action"))],
+    ///     2,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     FirstHopStrategy::TopDegree
+    /// );
+    ///
+    /// assert_eq!(recommendations[0], RecommenderNode::Object(rocky));
+    /// ```
+    pub fn recommendations_with_first_hop(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        first_hop_strategy: FirstHopStrategy,
+    ) -> Vec<RecommenderNode<T>> {
+        let weight_fun = |from: &RecommenderNode<T>, to: &RecommenderNode<T>| {
+            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+        };
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                let top_degree = self
+                    .graph
+                    .successors(q)
+                    .iter()
+                    .map(|node| self.graph.degree(node))
+                    .max()
+                    .unwrap_or(0);
+                let first_hop_weight =
+                    |from: &RecommenderNode<T>, to: &RecommenderNode<T>| match first_hop_strategy {
+                        FirstHopStrategy::Weighted => weight_fun(from, to),
+                        FirstHopStrategy::Uniform => 1.0,
+                        FirstHopStrategy::TopDegree if self.graph.degree(to) == top_degree => 1.0,
+                        FirstHopStrategy::TopDegree => 0.0,
+                    };
+                let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+                Recommender::accumulate_walks(
+                    steps,
+                    || match q {
+                        RecommenderNode::Tag(_) => self.graph.random_walk_with_first_hop(
+                            q,
+                            depth,
+                            first_hop_weight,
+                            weight_fun,
+                        ),
+                        RecommenderNode::Object(_) => self.graph.random_walk(q, depth, weight_fun),
+                    },
+                    |visits| {
+                        for visited in visits {
+                            *acc.entry(visited).or_insert(0) += 1;
+                        }
+                    },
+                );
+                acc
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the
+    /// `blocked` nodes are removed from the graph during the walks.
+    ///
+    /// Unlike filtering the results, this also prevents the blocked nodes
+    /// from connecting the queries to other nodes, so everything that is
+    /// only reachable through them is also excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Drama");
+    ///
+    /// let mut blocked = HashSet::new();
+    /// blocked.insert(RecommenderNode::Object(rocky));
+    ///
+    /// let recommendations = recommender.recommendations_with_hard_block(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     &blocked,
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![RecommenderNode::Tag(String::from("Action"))]);
+    /// ```
+    pub fn recommendations_with_hard_block(
+        &self,
+        queries: &[RecommenderNode<T>],
+        blocked: &HashSet<RecommenderNode<T>>,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let unblocked_queries = queries
+            .iter()
+            .filter(|q| !blocked.contains(q))
+            .cloned()
+            .collect::<Vec<RecommenderNode<T>>>();
+        let all_recommendations =
+            self.aggregated_recommendations(&unblocked_queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    if blocked.contains(to) {
+                        0.0
+                    } else {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    }
+                })
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the
+    /// degree of each query is provided by the caller, instead of being
+    /// looked up in the graph.
+    ///
+    /// The degrees are trusted, so wrong values only affect how the steps
+    /// are split among the queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_with_degrees(
+    ///     &[(RecommenderNode::Object(raid), 1)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_with_degrees(
+        &self,
+        queries: &[(RecommenderNode<T>, usize)],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let query_nodes = queries
+            .iter()
+            .map(|(q, _)| q.clone())
+            .collect::<Vec<RecommenderNode<T>>>();
+        let query_budgets =
+            self.degree_budgets(queries.iter().map(|(_, degree)| *degree), max_total_steps);
+
+        let all_recommendations: HashMap<RecommenderNode<T>, f64> = self
+            .covered_recommendations(&query_nodes, query_budgets, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            })
+            .into_iter()
+            .map(|(k, (score, _))| (k, score))
+            .collect();
+
+        self.rank_recommendations(&all_recommendations, |node| !query_nodes.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the depth
+    /// of the walks is given in object to object hops, instead of graph
+    /// hops.
+    ///
+    /// Going from an object to another object always goes through a tag,
+    /// so each logical hop corresponds to two graph hops. The walks stop
+    /// after visiting `logical_depth` objects (besides the query itself),
+    /// capped at the maximum depth of a walk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_with_logical_depth(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     1,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_with_logical_depth(
+        &self,
+        queries: &[RecommenderNode<T>],
+        logical_depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                let object_visits = match q {
+                    RecommenderNode::Object(_) => 1,
+                    RecommenderNode::Tag(_) => 0,
+                };
+                // Every other node is an object, starting with the query
+                // if it is an object.
+                let depth = (2 * u16::from(logical_depth) + object_visits).min(u16::from(u8::MAX));
+                self.recommendations_map(q, depth as u8, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but each query
+    /// has a confidence, between `0.0` and `1.0`, that scales its
+    /// contribution to the final scores.
+    ///
+    /// The confidence does not change how the steps are split among the
+    /// queries, so a low confidence query is still fully explored, but
+    /// only nudges the results. Confidences outside of the valid range are
+    /// clamped (and `NaN` is treated as `0.0`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    /// let brian = String::from("Life of Brian");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    /// recommender.tag_object(&brian, "Comedy");
+    ///
+    /// let recommendations = recommender.recommendations_with_confidence(
+    ///     &[
+    ///         (RecommenderNode::Object(raid), 1.0),
+    ///         (RecommenderNode::Object(python), 0.1)
+    ///     ],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// let rocky_position = recommendations
+    ///     .iter()
+    ///     .position(|node| *node == RecommenderNode::Object(rocky.clone()))
+    ///     .unwrap();
+    /// let brian_position = recommendations
+    ///     .iter()
+    ///     .position(|node| *node == RecommenderNode::Object(brian.clone()))
+    ///     .unwrap();
+    /// assert!(rocky_position < brian_position);
+    /// ```
+    pub fn recommendations_with_confidence(
+        &self,
+        queries: &[(RecommenderNode<T>, f64)],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let query_nodes = queries
+            .iter()
+            .map(|(q, _)| q.clone())
+            .collect::<Vec<RecommenderNode<T>>>();
+        let all_recommendations = self.confidence_weighted_recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            |from, to| self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight),
+        );
+
+        self.rank_recommendations(&all_recommendations, |node| !query_nodes.contains(node))
+    }
+
+    /// Receives a set of weighted queries (that can be either tags or
+    /// objects) and returns an ordered sequence of recommendations (with
+    /// the first one being the "best" one).
+    ///
+    /// As described in the Pixie paper, the steps of each query are
+    /// proportional to its weight (e.g. the affinity of the user with the
+    /// query) times its degree-based scaling factor. Queries with a weight
+    /// less than or equal to zero contribute no steps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    /// let brian = String::from("Life of Brian");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    /// recommender.tag_object(&brian, "Comedy");
+    ///
+    /// let recommendations = recommender.recommendations_weighted(
+    ///     &[
+    ///         (RecommenderNode::Object(raid), 1.0),
+    ///         (RecommenderNode::Object(python), 0.0)
+    ///     ],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// assert!(!recommendations.contains(&RecommenderNode::Object(brian)));
+    /// ```
+    pub fn recommendations_weighted(
+        &self,
+        queries: &[(RecommenderNode<T>, f32)],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let query_nodes = queries
+            .iter()
+            .map(|(q, _)| q.clone())
+            .collect::<Vec<RecommenderNode<T>>>();
+        let all_recommendations = self
+            .covered_recommendations(
+                &query_nodes,
+                self.weighted_query_budgets(queries, max_total_steps),
+                |_, q, steps| {
+                    self.recommendations_map(q, depth, steps, |from, to| {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    })
+                },
+            )
+            .into_iter()
+            .map(|(k, (score, _))| (k, score))
+            .collect();
+
+        self.rank_recommendations(&all_recommendations, |node| !query_nodes.contains(node))
+    }
+
+    fn confidence_weighted_recommendations(
+        &self,
+        queries: &[(RecommenderNode<T>, f64)],
+        depth: u8,
+        max_total_steps: usize,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> HashMap<RecommenderNode<T>, f64> {
+        let query_nodes = queries
+            .iter()
+            .map(|(q, _)| q.clone())
+            .collect::<Vec<RecommenderNode<T>>>();
+        // The counts of each query are combined by their square root, so
+        // scaling them by the squared confidence scales the contribution of
+        // the query by the confidence.
+        self.aggregated_recommendations(&query_nodes, max_total_steps, |i, q, steps| {
+            let confidence = queries[i].1;
+            let confidence = if confidence.is_nan() {
+                0.0
+            } else {
+                confidence.clamp(0.0, 1.0)
+            };
+            self.recommendations_map(q, depth, steps, &weight_fun)
+                .into_iter()
+                .map(|(k, v)| (k, f64::from(v) * confidence * confidence))
+                .collect::<HashMap<RecommenderNode<T>, f64>>()
+        })
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but only the
+    /// kinds of nodes selected by the accumulation `mode` are counted and
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::AccumulationMode;
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    ///
+    /// let recommendations = recommender.recommendations_with_mode(
+    ///     &[RecommenderNode::Tag(String::from("Action"))],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     AccumulationMode::TagsOnly
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![RecommenderNode::Tag(String::from("Drama"))]);
+    /// ```
+    pub fn recommendations_with_mode(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        mode: AccumulationMode,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map_with_mode(
+                    q,
+                    depth,
+                    steps,
+                    |from, to| {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    },
+                    mode,
+                )
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the visits
+    /// of the walks of each query are combined according to `aggregation`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::WalkAggregation;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_with_aggregation(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     WalkAggregation::VisitRate
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![
+    ///     RecommenderNode::Tag(String::from("Action")),
+    ///     RecommenderNode::Object(rocky)
+    /// ]);
+    /// ```
+    pub fn recommendations_with_aggregation(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        aggregation: WalkAggregation,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+                Recommender::accumulate_walks(
+                    steps,
+                    || {
+                        self.graph.random_walk(q, depth, |from, to| {
+                            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                        })
+                    },
+                    |visits| aggregation.record(&mut acc, visits),
+                );
+                acc
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    fn degree_budget(&self, queries: &[RecommenderNode<T>], steps_per_degree: usize) -> usize {
+        queries
+            .iter()
+            .map(|q| self.graph.degree(q) * steps_per_degree)
+            .sum()
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the total
+    /// number of steps is derived from the queries, instead of being fixed.
+    ///
+    /// Each query contributes `steps_per_degree` steps for each of its
+    /// neighbors, so well connected queries automatically get a larger
+    /// walk budget.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// let action = String::from("Action");
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    ///
+    /// let recommendations = recommender.recommendations_auto_budget(
+    ///     &vec![RecommenderNode::Object(raid)],
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_auto_budget(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        steps_per_degree: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        self.recommendations(
+            queries,
+            depth,
+            self.degree_budget(queries, steps_per_degree),
+            object_to_tag_weight,
+            tag_to_object_weight,
+        )
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the visits
+    /// are counted approximately using a Count-Min Sketch.
+    ///
+    /// Only the `top_k` most visited nodes of each query are tracked, so the
+    /// memory usage is bounded, regardless of how many distinct nodes
+    /// are visited. This is useful on huge graphs, where only the best
+    /// recommendations matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::sketch::SketchConfig;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// let action = String::from("Action");
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    ///
+    /// let recommendations = recommender.approximate_recommendations(
+    ///     &vec![RecommenderNode::Object(raid)],
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &SketchConfig::new(256, 4, 10)
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn approximate_recommendations(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        config: &SketchConfig,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.approximate_recommendations_map(
+                    q,
+                    depth,
+                    steps,
+                    |from, to| {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    },
+                    config,
+                )
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but the walks
+    /// of each query stop as soon as enough candidates were found.
+    ///
+    /// See [`EarlyStopping`] for the available options, including the
+    /// adaptive walk scheduling.
+    ///
+    /// [`EarlyStopping`]: early_stopping/struct.EarlyStopping.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::early_stopping::EarlyStopping;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_early_stopping(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &EarlyStopping::new(5, 2).adaptive(true)
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_early_stopping(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        config: &EarlyStopping,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.early_stopping_recommendations_map(
+                    q,
+                    depth,
+                    steps,
+                    |from, to| {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    },
+                    config,
+                )
+                .0
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Same as
+    /// [`object_recommendations`](#method.object_recommendations), but the
+    /// walks of each query stop as soon as enough candidates were found.
+    ///
+    /// Only objects count as candidates. See
+    /// [`recommendations_early_stopping`](#method.recommendations_early_stopping).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::early_stopping::EarlyStopping;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_early_stopping(
+    ///     &[raid],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &EarlyStopping::new(5, 1)
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn object_recommendations_early_stopping(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        config: &EarlyStopping,
+    ) -> Vec<T> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        let all_recommendations =
+            self.aggregated_recommendations(&node_queries, max_total_steps, |_, q, steps| {
+                self.early_stopping_recommendations_map_with(
+                    q,
+                    depth,
+                    steps,
+                    |from, to| {
+                        self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                    },
+                    config,
+                    |node| matches!(node, RecommenderNode::Object(_)),
+                )
+                .0
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => false,
+            RecommenderNode::Object(obj) => !queries.contains(obj),
+        })
+        .into_iter()
+        .flat_map(|node| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj),
+        })
+        .collect()
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but objects
+    /// that are reached through multiple distinct tags get a higher score.
+    ///
+    /// The visit count of each object is multiplied by
+    /// `1 + shared_tag_boost * (distinct_tags - 1)`, where `distinct_tags` is
+    /// the number of distinct tags that the walks went through right before
+    /// visiting that object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&raid, "Drama");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Drama");
+    ///
+    /// let recommendations = recommender
+    ///     .recommendations_with_tag_boost(
+    ///         &[RecommenderNode::Object(raid)],
+    ///         3,
+    ///         1000,
+    ///         |_, _| 1.0,
+    ///         |_, _| 1.0,
+    ///         1.0
+    ///     )
+    ///     .into_iter()
+    ///     .filter(|node| match node {
+    ///         RecommenderNode::Tag(_) => false,
+    ///         RecommenderNode::Object(_) => true
+    ///     })
+    ///     .collect::<Vec<RecommenderNode<String>>>();
+    ///
+    /// assert_eq!(recommendations[0], RecommenderNode::Object(rocky));
+    /// ```
+    pub fn recommendations_with_tag_boost(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        shared_tag_boost: f64,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                let tagged_visits = self.tagged_recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                });
+                Recommender::tag_boosted_counts(&tagged_visits, shared_tag_boost)
+            });
+
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Receives an object and one of its tags and returns an ordered
+    /// sequence of recommendations (with the first one being the "best" one).
+    ///
+    /// Every walk starts by going from the object to the given tag, so the
+    /// results are biased towards the context of that tag (e.g. "because you
+    /// watched this Action movie"). If the object is not tagged with `tag`,
+    /// no recommendations are returned.
+    ///
+    /// Both the object and the tag are excluded from the results.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// let action = String::from("Action");
+    /// let comedy = String::from("Comedy");
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&raid, &comedy);
+    /// recommender.tag_object(&rocky, &action);
+    /// recommender.tag_object(&python, &comedy);
+    ///
+    /// let recommendations = recommender.edge_recommendations(
+    ///     &raid,
+    ///     &action,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![RecommenderNode::Object(rocky)]);
+    /// ```
+    pub fn edge_recommendations(
+        &self,
+        object: &T,
+        tag: &str,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let object_node = RecommenderNode::Object(object.clone());
+        let tag_node = RecommenderNode::Tag(String::from(tag));
+        let weight_fun = |from: &RecommenderNode<T>, to: &RecommenderNode<T>| {
+            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+        };
+        let mut all_recommendations: HashMap<RecommenderNode<T>, f64> = HashMap::new();
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || {
+                self.graph
+                    .random_walk_from_edge(&object_node, &tag_node, depth, weight_fun)
+            },
+            |visits| {
+                for visited in visits {
+                    *all_recommendations.entry(visited).or_insert(0.0) += 1.0;
+                }
+            },
+        );
+        for score in all_recommendations.values_mut() {
+            *score = score.sqrt();
+        }
+
+        self.rank_recommendations(&all_recommendations, |node| {
+            *node != object_node && *node != tag_node
+        })
+    }
+
+    /// Receives a set of queries (that can only objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one).
+    ///
+    /// This is a simplified version of the `recommendations` operation
+    /// that only returns objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// let action = String::from("Action");
+    /// let comedy = String::from("Comedy");
+    /// let drama = String::from("Drama");
+    ///
+    /// recommender.add_object(&raid);
+    /// recommender.add_object(&rocky);
+    /// recommender.add_object(&python);
+    ///
+    /// recommender.add_tag(&action);
+    /// recommender.add_tag(&comedy);
+    /// recommender.add_tag(&drama);
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    /// recommender.tag_object(&rocky, &drama);
+    /// recommender.tag_object(&python, &comedy);
+    ///
+    /// let recommendations = recommender
+    ///     .object_recommendations(
+    ///         &vec![raid.clone()],
+    ///         50,
+    ///         50,
+    ///         |_, _| 1.0,
+    ///         |_, _| 1.0
+    ///     )
+    ///     .iter()
+    ///     .cloned()
+    ///     .collect::<Vec<String>>();
+    ///
+    /// assert!(recommendations.len() > 0);
+    /// assert!(recommendations[0] == rocky)
+    /// ```
+    pub fn object_recommendations(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        self.recommendations(
+            &node_queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        )
+        .iter()
+        .flat_map(|node| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj.clone()),
+        })
+        .collect()
+    }
+
+    /// Receives a set of object queries and returns an ordered sequence
+    /// of tag recommendations (with the first one being the "best" one).
+    ///
+    /// This is a simplified version of the `recommendations` operation
+    /// that only returns tags, e.g. to suggest new categories for a set of
+    /// objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let tags = recommender.recommend_tags(&[raid], 5, 100, |_, _| 1.0, |_, _| 1.0);
+    ///
+    /// assert_eq!(tags[0], "Action");
+    /// assert!(tags.contains(&String::from("Drama")));
+    /// assert!(!tags.contains(&String::from("Comedy")));
+    /// ```
+    pub fn recommend_tags(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<String> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        self.recommendations(
+            &node_queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        )
+        .into_iter()
+        .flat_map(|node| match node {
+            RecommenderNode::Tag(tag) => Some(tag),
+            RecommenderNode::Object(_) => None,
+        })
+        .collect()
+    }
+
+    /// Hardened version of
+    /// [`object_recommendations`](#method.object_recommendations), for
+    /// weight functions that can't be trusted.
+    ///
+    /// Every weight is sanitized with [`sanitize_weight`], so the walks and
+    /// the split of the steps never produce NaN or infinite values. Queries
+    /// that can't be walked (e.g. because all of their weights are invalid
+    /// or they have no edges) don't contribute, but the other queries still
+    /// do.
+    ///
+    /// [`sanitize_weight`]: graph/fn.sanitize_weight.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_sanitized(
+    ///     &[raid.clone()],
+    ///     3,
+    ///     100,
+    ///     |_, _| f32::NAN,
+    ///     |_, _| f32::INFINITY
+    /// );
+    /// assert!(recommendations.is_empty());
+    ///
+    /// let recommendations = recommender.object_recommendations_sanitized(
+    ///     &[raid],
+    ///     3,
+    ///     100,
+    ///     |_, _| f32::MAX,
+    ///     |_, _| f32::MAX
+    /// );
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn object_recommendations_sanitized(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        self.object_recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            |obj, tag| sanitize_weight(object_to_tag_weight(obj, tag)),
+            |tag, obj| sanitize_weight(tag_to_object_weight(tag, obj)),
+        )
+    }
+
+    /// Same as [`object_recommendations`](#method.object_recommendations),
+    /// but the recommendations are borrowed from the recommender, instead
+    /// of being cloned.
+    ///
+    /// This is useful when the objects are expensive to clone and the
+    /// results are only read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender
+    ///     .object_recommendations_iter(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0)
+    ///     .collect::<Vec<&String>>();
+    ///
+    /// assert_eq!(recommendations, vec![&rocky]);
+    /// ```
+    pub fn object_recommendations_iter<'a>(
+        &'a self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> impl Iterator<Item = &'a T> + 'a {
+        self.object_recommendations_filtered(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+            |_| true,
+        )
+        .into_iter()
+        .flat_map(
+            move |obj| match self.graph.stored_node(&RecommenderNode::Object(obj)) {
+                Some(RecommenderNode::Object(stored)) => Some(stored),
+                _ => None,
+            },
+        )
+    }
+
+    /// Same as [`object_recommendations`](#method.object_recommendations),
+    /// but the walks use the given random number generator.
+    ///
+    /// Calling this method twice on the same recommender with generators
+    /// in the same state returns the same recommendations, including the
+    /// order of the ties.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let recommendations = recommender.object_recommendations_seeded(
+    ///     &[raid],
+    ///     50,
+    ///     50,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &mut StdRng::seed_from_u64(42)
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn object_recommendations_seeded(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        rng: &mut impl Rng,
+    ) -> Vec<T> {
+        self.seeded_object_scores(queries, depth, max_total_steps, rng, None, |from, to| {
+            self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+        })
+        .into_iter()
+        .map(|(obj, _)| obj)
+        .collect()
+    }
+
+    /// Same as
+    /// [`object_recommendations_seeded`](#method.object_recommendations_seeded),
+    /// but each object is returned along with its score.
+    ///
+    /// If a `score_precision` is given, the scores are rounded to that
+    /// number of decimal places, so that tiny differences between calls
+    /// don't change the results. Objects whose rounded scores are equal
+    /// keep the order in which they were first visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_scored_seeded(
+    ///     &[raid],
+    ///     3,
+    ///     1000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     &mut StdRng::seed_from_u64(42),
+    ///     Some(0)
+    /// );
+    ///
+    /// assert_eq!(recommendations.len(), 1);
+    /// assert_eq!(recommendations[0].0, rocky);
+    /// assert_eq!(recommendations[0].1, recommendations[0].1.round());
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn object_recommendations_scored_seeded(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        rng: &mut impl Rng,
+        score_precision: Option<u32>,
+    ) -> Vec<(T, f64)> {
+        self.seeded_object_scores(
+            queries,
+            depth,
+            max_total_steps,
+            rng,
+            score_precision,
+            |from, to| self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight),
+        )
+    }
+
+    /// Scores of the objects visited by the walks, sorted by descending
+    /// score, rounded to `score_precision`. The ties keep the order in
+    /// which the objects were first visited.
+    fn seeded_object_scores(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        rng: &mut impl Rng,
+        score_precision: Option<u32>,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> Vec<(T, f64)> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        let query_budgets = self.query_budgets(&node_queries, max_total_steps);
+
+        let mut visit_order: Vec<RecommenderNode<T>> = Vec::new();
+        let mut all_recommendations: HashMap<RecommenderNode<T>, f64> = HashMap::new();
+        for (q, max_steps) in node_queries.iter().zip(query_budgets) {
+            let mut query_order: Vec<RecommenderNode<T>> = Vec::new();
+            let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+            Recommender::accumulate_walks(
+                max_steps,
+                || self.graph.random_walk_with_rng(q, depth, &weight_fun, rng),
+                |visits| {
+                    // The walks list the visited nodes in reverse order
+                    for visited in visits.into_iter().rev() {
+                        *acc.entry(visited).or_insert_with_key(|node| {
+                            query_order.push(node.clone());
+                            0
+                        }) += 1;
+                    }
+                },
+            );
+            for node in query_order {
+                let value_sqrt = (acc[&node] as f64).sqrt();
+                *all_recommendations.entry(node).or_insert_with_key(|node| {
+                    visit_order.push(node.clone());
+                    0.0
+                }) += value_sqrt;
+            }
+        }
+
+        self.rank_ordered_recommendations(
+            visit_order.iter().map(|node| {
+                let score = all_recommendations[node] * all_recommendations[node];
+                (node, round_score(score, score_precision))
+            }),
+            |node| match node {
+                RecommenderNode::Tag(_) => false,
+                RecommenderNode::Object(obj) => !queries.contains(obj),
+            },
+        )
+        .into_iter()
+        .flat_map(|(node, score)| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some((obj, score)),
+        })
+        .collect()
+    }
+
+    /// Receives a set of queries (that can only be objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one), where recommendations that share the same
+    /// key are collapsed into a single entry.
+    ///
+    /// This is useful to group near-duplicate objects (e.g. multiple
+    /// editions of the same movie). Only the best ranked object of each
+    /// group is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let rocky_remastered = String::from("Rocky (Remastered)");
+    ///
+    /// let action = String::from("Action");
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    /// recommender.tag_object(&rocky_remastered, &action);
+    ///
+    /// let recommendations = recommender.object_recommendations_dedup_by(
+    ///     &vec![raid.clone()],
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     |movie| movie.split(" (").next().unwrap().to_string()
+    /// );
+    ///
+    /// assert_eq!(recommendations.len(), 1);
+    /// ```
+    pub fn object_recommendations_dedup_by<K: Eq + Hash>(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        key_fun: impl Fn(&T) -> K,
+    ) -> Vec<T> {
+        let mut seen_keys: HashSet<K> = HashSet::new();
+        self.object_recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        )
+        .into_iter()
+        .filter(|obj| seen_keys.insert(key_fun(obj)))
+        .collect()
+    }
+
+    /// Receives a set of queries (that can only be objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one), restricted to the `allowed` objects.
+    ///
+    /// The walks still go through every node of the graph, so objects
+    /// outside of the allowed set can still connect the queries to the
+    /// allowed objects. They are simply never returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// let action = String::from("Action");
+    /// let drama = String::from("Drama");
+    ///
+    /// recommender.tag_object(&raid, &action);
+    /// recommender.tag_object(&rocky, &action);
+    /// recommender.tag_object(&rocky, &drama);
+    /// recommender.tag_object(&creed, &drama);
+    ///
+    /// let mut in_stock: HashSet<String> = HashSet::new();
+    /// in_stock.insert(creed.clone());
+    ///
+    /// let recommendations = recommender.object_recommendations_within(
+    ///     &vec![raid.clone()],
+    ///     &in_stock,
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![creed]);
+    /// ```
+    pub fn object_recommendations_within(
+        &self,
+        queries: &[T],
+        allowed: &HashSet<T>,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        self.object_recommendations_filtered(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+            |obj| allowed.contains(obj),
+        )
+    }
+
+    /// Receives a query object and returns an ordered sequence of
+    /// recommendations (with the first one being the "best" one), only
+    /// including objects tagged with `tag`.
+    ///
+    /// This can be used for "more like this, within this genre"
+    /// recommendations. The walks can still go through other tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Drama");
+    ///
+    /// let recommendations = recommender.object_recommendations_within_tag(
+    ///     &raid,
+    ///     "Drama",
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&rocky));
+    /// ```
+    pub fn object_recommendations_within_tag(
+        &self,
+        query: &T,
+        tag: &str,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        let members = self
+            .graph
+            .successors(&RecommenderNode::Tag(String::from(tag)));
+        self.object_recommendations_filtered(
+            std::slice::from_ref(query),
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+            |obj| members.contains(&RecommenderNode::Object(obj.clone())),
+        )
+    }
+
+    /// Receives a set of queries (that can only be objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one), excluding objects with more than
+    /// `max_candidate_degree` tags.
+    ///
+    /// Objects that are connected to almost everything (e.g. generic
+    /// blockbusters) are often poor recommendations, so this allows more
+    /// specific objects to surface.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let blockbuster = String::from("Blockbuster");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&blockbuster, "Action");
+    /// recommender.tag_object(&blockbuster, "Comedy");
+    /// recommender.tag_object(&blockbuster, "Drama");
+    ///
+    /// let recommendations = recommender.object_recommendations_max_degree(
+    ///     &[raid],
+    ///     2,
+    ///     10,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![rocky]);
+    /// ```
+    pub fn object_recommendations_max_degree(
+        &self,
+        queries: &[T],
+        max_candidate_degree: usize,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<T> {
+        self.object_recommendations_filtered(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+            |obj| self.graph.degree(&RecommenderNode::Object(obj.clone())) <= max_candidate_degree,
+        )
+    }
+
+    /// Receives a set of queries (that can only be objects) and returns
+    /// the recommendations grouped by the tag that most strongly connects
+    /// each of them to the queries.
+    ///
+    /// The dominant tag of an object is the tag that the walks went through
+    /// most often right before visiting it. Each group is ordered from the
+    /// best to the worst recommendation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&raid, "Comedy");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let groups = recommender.object_recommendations_grouped_by_tag(
+    ///     &[raid],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert_eq!(groups["Action"], vec![rocky]);
+    /// assert_eq!(groups["Comedy"], vec![python]);
+    /// ```
+    pub fn object_recommendations_grouped_by_tag(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> HashMap<String, Vec<T>> {
+        let (all_recommendations, via_tags) = self.tagged_aggregated_recommendations(
+            queries,
+            depth,
+            max_total_steps,
+            object_to_tag_weight,
+            tag_to_object_weight,
+        );
+
+        let mut groups: HashMap<String, Vec<T>> = HashMap::new();
+        let ranked = self.rank_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => false,
+            RecommenderNode::Object(obj) => !queries.contains(obj),
+        });
+        for node in ranked {
+            let dominant_tag = via_tags.get(&node).and_then(|tags| {
+                tags.iter()
+                    .max_by(|(tag_a, a), (tag_b, b)| a.cmp(b).then_with(|| tag_b.cmp(tag_a)))
+                    .map(|(tag, _)| tag.clone())
+            });
+            if let (RecommenderNode::Object(obj), Some(tag)) = (node, dominant_tag) {
+                groups.entry(tag).or_default().push(obj);
+            }
+        }
+        groups
+    }
+
+    /// Same as [`object_recommendations`](#method.object_recommendations),
+    /// but recently added objects get a higher score, to help new objects
+    /// overcome the lack of relationships.
+    ///
+    /// The score of each object is multiplied by
+    /// `1 + freshness_boost / (1 + age)`, where `age` is the number of
+    /// objects that were added to the recommender after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&creed, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_with_freshness(
+    ///     &[raid],
+    ///     3,
+    ///     30000,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     1.0
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![creed, rocky]);
+    /// ```
+    pub fn object_recommendations_with_freshness(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        freshness_boost: f64,
+    ) -> Vec<T> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        let mut all_recommendations =
+            self.aggregated_recommendations(&node_queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            });
+        self.apply_freshness(&mut all_recommendations, freshness_boost);
+
+        self.rank_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => false,
+            RecommenderNode::Object(obj) => !queries.contains(obj),
+        })
+        .into_iter()
+        .flat_map(|node| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj),
+        })
+        .collect()
+    }
+
+    /// Same as [`recommendations`](#method.recommendations), but
+    /// candidates connected to a large part of the graph get a lower
+    /// score, since they would be recommended for almost any query.
+    ///
+    /// The score of each candidate is multiplied by an inverse frequency
+    /// `1 + ln((1 + objects) / (1 + reach))`, where `objects` is the number
+    /// of objects in the recommender and `reach` is the number of objects
+    /// that the candidate is connected to (the objects of a tag, or the
+    /// other objects of each tag of an object, so objects that share
+    /// multiple tags are counted once per tag).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_with_idf(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_with_idf(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let mut all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            });
+        self.apply_inverse_frequency(&mut all_recommendations);
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Receives a set of seeds (that can be either tags or objects) and
+    /// returns an ordered sequence of recommendations (with the first one
+    /// being the "best" one), blending the personalized walks from the
+    /// seeds with the global popularity of each node.
+    ///
+    /// Both signals are normalized to `[0, 1]` (by the best personalized
+    /// score and by the maximum degree) and combined as
+    /// `(1 - global_weight) * personalized + global_weight * popularity`,
+    /// so with a `global_weight` of `0.0` only the walks matter and with
+    /// `1.0` the nodes are ranked by degree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    /// recommender.tag_object(&python, "Satire");
+    /// recommender.tag_object(&python, "Classic");
+    ///
+    /// let seeds = [RecommenderNode::Object(raid)];
+    /// let personalized =
+    ///     recommender.recommendations_blended(&seeds, 0.0, 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert!(!personalized.contains(&RecommenderNode::Object(python.clone())));
+    ///
+    /// let popular =
+    ///     recommender.recommendations_blended(&seeds, 1.0, 3, 100, |_, _| 1.0, |_, _| 1.0);
+    /// assert_eq!(popular[0], RecommenderNode::Object(python));
+    /// ```
+    pub fn recommendations_blended(
+        &self,
+        seeds: &[RecommenderNode<T>],
+        global_weight: f64,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let global_weight = if global_weight.is_nan() {
+            0.0
+        } else {
+            global_weight.clamp(0.0, 1.0)
+        };
+        let personalized = if global_weight < 1.0 {
+            self.aggregated_recommendations(seeds, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            })
+        } else {
+            HashMap::new()
+        };
+        let max_score = personalized
+            .iter()
+            .filter(|(node, _)| !seeds.contains(node))
+            .map(|(_, score)| *score)
+            .fold(0.0, f64::max);
+        let max_degree = self.graph.max_degree() as f64;
+
+        let is_blocked = self.blocked_objects();
+        let mut blended = self
+            .graph
+            .nodes()
+            .filter(|node| !seeds.contains(node) && !node.as_object().is_some_and(&is_blocked))
+            .map(|node| {
+                let personalized_score = match personalized.get(node) {
+                    Some(score) if max_score > 0.0 => score / max_score,
+                    _ => 0.0,
+                };
+                let popularity = if max_degree > 0.0 {
+                    self.graph.degree(node) as f64 / max_degree
+                } else {
+                    0.0
+                };
+                let score = (1.0 - global_weight) * personalized_score + global_weight * popularity;
+                (node, score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect::<Vec<(&RecommenderNode<T>, f64)>>();
+        blended.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        blended.into_iter().map(|(node, _)| node.clone()).collect()
+    }
+
+    /// Multiplies the final score of each object by its freshness
+    /// multiplier. The aggregated scores are squared by the ranking, so
+    /// they are multiplied by the square root of the multiplier.
+    fn apply_freshness(&self, scores: &mut HashMap<RecommenderNode<T>, f64>, freshness_boost: f64) {
+        for (node, score) in scores.iter_mut() {
+            if let Some(insertion) = node
+                .as_object()
+                .and_then(|obj| self.insertion_order.get(obj))
+            {
+                let age = self.next_insertion.saturating_sub(insertion + 1) as f64;
+                *score *= (1.0 + freshness_boost / (1.0 + age)).max(0.0).sqrt();
+            }
+        }
+    }
+
+    /// Multiplies each score by the inverse frequency of its node.
+    fn apply_inverse_frequency(&self, scores: &mut HashMap<RecommenderNode<T>, f64>) {
+        let objects = self
+            .graph
+            .nodes()
+            .filter(|node| matches!(node, RecommenderNode::Object(_)))
+            .count() as f64;
+        for (node, score) in scores.iter_mut() {
+            let reach = self.object_reach(node) as f64;
+            *score *= 1.0 + ((1.0 + objects) / (1.0 + reach)).ln();
+        }
+    }
+
+    /// Number of objects of a tag, or the sum of the number of other
+    /// objects of each tag of an object. Only the degrees of the tags are
+    /// read, so this is linear on the degree of the node.
+    fn object_reach(&self, node: &RecommenderNode<T>) -> usize {
+        match node {
+            RecommenderNode::Tag(_) => self.graph.degree(node),
+            RecommenderNode::Object(_) => self
+                .graph
+                .successors(node)
+                .iter()
+                .filter(|tag| matches!(tag, RecommenderNode::Tag(_)))
+                .map(|tag| self.graph.degree(tag).saturating_sub(1))
+                .sum(),
+        }
+    }
+
+    /// Receives a set of queries (that can only be objects) and returns
+    /// an ordered sequence of recommendations (with the first one being
+    /// the "best" one), only including objects that satisfy `filter`.
+    ///
+    /// The filter is applied while ranking, so the filtered objects are
+    /// still traversed by the walks, but never take the place of other
+    /// recommendations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<(String, u32)> = Recommender::new();
+    ///
+    /// let raid = (String::from("The Raid"), 2011);
+    /// let rocky = (String::from("Rocky"), 1976);
+    /// let creed = (String::from("Creed"), 2015);
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&creed, "Action");
+    ///
+    /// let recommendations = recommender.object_recommendations_filtered(
+    ///     &[raid],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0,
+    ///     |(_, year)| *year > 2000
+    /// );
+    ///
+    /// assert_eq!(recommendations, vec![creed]);
+    /// ```
+    pub fn object_recommendations_filtered(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+        filter: impl Fn(&T) -> bool,
+    ) -> Vec<T> {
+        let node_queries: Vec<RecommenderNode<T>> = queries
+            .iter()
+            .map(|x| RecommenderNode::Object(x.clone()))
+            .collect();
+        let all_recommendations =
+            self.aggregated_recommendations(&node_queries, max_total_steps, |_, q, steps| {
+                self.recommendations_map(q, depth, steps, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            });
+        self.rank_recommendations(&all_recommendations, |node| match node {
+            RecommenderNode::Tag(_) => false,
+            RecommenderNode::Object(obj) => !queries.contains(obj) && filter(obj),
+        })
+        .into_iter()
+        .flat_map(|node| match node {
+            RecommenderNode::Tag(_) => None,
+            RecommenderNode::Object(obj) => Some(obj),
+        })
+        .collect()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Eq + Clone + Hash + Send + Sync + 'static> Recommender<T> {
+    /// Asynchronous version of [`recommendations`](#method.recommendations).
+    ///
+    /// The random walks are CPU bound, so they are executed on tokio's
+    /// blocking thread pool, to avoid blocking the executor.
+    ///
+    /// This method is only available with the `tokio` feature.
+    pub async fn recommendations_async(
+        self: Arc<Self>,
+        queries: Vec<RecommenderNode<T>>,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32 + Send + 'static,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32 + Send + 'static,
+    ) -> Vec<RecommenderNode<T>> {
+        tokio::task::spawn_blocking(move || {
+            self.recommendations(
+                &queries,
+                depth,
+                max_total_steps,
+                object_to_tag_weight,
+                tag_to_object_weight,
+            )
+        })
+        .await
+        .expect("Failed to compute the recommendations")
+    }
+}
+
+/// Rounds a score to the given number of decimal places (if any).
+fn round_score(score: f64, precision: Option<u32>) -> f64 {
+    match precision {
+        // f64 can't represent more than 15 decimal digits exactly
+        Some(precision) if precision <= 15 => {
+            let scale = 10f64.powi(precision as i32);
+            (score * scale).round() / scale
+        }
+        _ => score,
+    }
+}
+
+fn bipartite_weight<T>(
+    from: &RecommenderNode<T>,
+    to: &RecommenderNode<T>,
+    object_to_tag_weight: impl Fn(&T, &String) -> f32,
+    tag_to_object_weight: impl Fn(&String, &T) -> f32,
+) -> f32 {
+    match (from, to) {
+        (RecommenderNode::Tag(tag), RecommenderNode::Object(obj)) => tag_to_object_weight(tag, obj),
+        (RecommenderNode::Object(obj), RecommenderNode::Tag(tag)) => object_to_tag_weight(obj, tag),
+        _ => 0.0,
+    }
+}
+
+impl<T: Eq + Clone + Hash> Default for Recommender<T> {
+    fn default() -> Recommender<T> {
+        Recommender::new()
+    }
+}
+
+impl<T: Eq + Hash + fmt::Debug> fmt::Debug for Recommender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Recommender [{:?}]", self.graph)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dead_end_recommendations_map() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let obj_0 = String::from("0.0");
+        let tag_1 = String::from("1.0");
+        let obj_2 = String::from("2.0");
+
+        recommender.add_object(&obj_0);
+        recommender.add_tag(&tag_1);
+        recommender.add_object(&obj_2);
+
+        recommender.tag_object(&obj_0, &tag_1);
+        recommender.tag_object(&obj_2, &tag_1);
+
+        let recommendations = recommender.recommendations_map(
+            &RecommenderNode::Object(obj_0.clone()),
+            3,
+            3,
+            |from, to| match (from, to) {
+                (RecommenderNode::Tag(tag), RecommenderNode::Object(obj)) => {
+                    obj.parse::<f32>().unwrap() - tag.parse::<f32>().unwrap()
+                }
+                (RecommenderNode::Object(obj), RecommenderNode::Tag(tag)) => {
+                    tag.parse::<f32>().unwrap() - obj.parse::<f32>().unwrap()
+                }
+                _ => 0.0,
+            },
+        );
+
+        assert_eq!(
+            recommendations.get(&RecommenderNode::Tag(tag_1)).unwrap(),
+            &1
+        );
+        assert_eq!(
+            recommendations
+                .get(&RecommenderNode::Object(obj_2))
+                .unwrap(),
+            &1
+        );
+    }
+    #[test]
+    fn basic_recommendations() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let obj_0 = String::from("0.0");
+        let tag_1 = String::from("1.0");
+        let obj_2 = String::from("2.0");
+
+        recommender.add_object(&obj_0);
+        recommender.add_tag(&tag_1);
+        recommender.add_object(&obj_2);
+
+        recommender.tag_object(&obj_0, &tag_1);
+        recommender.tag_object(&obj_2, &tag_1);
+
+        let recommendations = recommender
+            .recommendations(
+                &[RecommenderNode::Object(obj_0.clone())],
+                10,
+                10,
+                |from, to| to.parse::<f32>().unwrap() - from.parse::<f32>().unwrap(),
+                |from, to| to.parse::<f32>().unwrap() - from.parse::<f32>().unwrap(),
+            )
+            .iter()
+            .cloned()
+            .collect::<HashSet<RecommenderNode<String>>>();
+
+        assert!(!recommendations.contains(&RecommenderNode::Object(obj_0)));
+        assert!(recommendations.contains(&RecommenderNode::Tag(tag_1)));
+        assert!(recommendations.contains(&RecommenderNode::Object(obj_2)));
+    }
+
+    #[test]
+    fn edge_recommendations_follow_the_seed_tag() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let query = String::from("query");
+        let action_a = String::from("action a");
+        let action_b = String::from("action b");
+        let drama_a = String::from("drama a");
+        let drama_b = String::from("drama b");
+
+        let action = String::from("Action");
+        let drama = String::from("Drama");
+
+        recommender.tag_object(&query, &action);
+        recommender.tag_object(&query, &drama);
+        recommender.tag_object(&action_a, &action);
+        recommender.tag_object(&action_b, &action);
+        recommender.tag_object(&drama_a, &drama);
+        recommender.tag_object(&drama_b, &drama);
+
+        let recommendations = recommender
+            .edge_recommendations(&query, &action, 3, 100, |_, _| 1.0, |_, _| 1.0)
+            .iter()
+            .cloned()
+            .collect::<HashSet<RecommenderNode<String>>>();
+
+        assert!(recommendations.contains(&RecommenderNode::Object(action_a)));
+        assert!(recommendations.contains(&RecommenderNode::Object(action_b)));
+        assert!(!recommendations.contains(&RecommenderNode::Object(drama_a)));
+        assert!(!recommendations.contains(&RecommenderNode::Object(drama_b)));
+        assert!(!recommendations.contains(&RecommenderNode::Object(query)));
+        assert!(!recommendations.contains(&RecommenderNode::Tag(action)));
+    }
+
+    #[test]
+    fn dedup_recommendations_by_key() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let rocky_collector = String::from("Rocky (Collector's Edition)");
+        let creed = String::from("Creed");
+
+        let action = String::from("Action");
+        let drama = String::from("Drama");
+
+        recommender.tag_object(&raid, &action);
+        recommender.tag_object(&rocky, &action);
+        recommender.tag_object(&rocky, &drama);
+        recommender.tag_object(&rocky_collector, &action);
+        recommender.tag_object(&rocky_collector, &drama);
+        recommender.tag_object(&creed, &drama);
+
+        let recommendations = recommender.object_recommendations_dedup_by(
+            std::slice::from_ref(&raid),
+            10,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            |movie| movie.split(" (").next().unwrap().to_string(),
+        );
+
+        let rockies = recommendations
+            .iter()
+            .filter(|movie| movie.starts_with("Rocky"))
+            .count();
+        assert_eq!(rockies, 1);
+        assert!(recommendations.contains(&creed));
+        assert!(!recommendations.contains(&raid));
+    }
+
+    #[test]
+    fn degree_budget_scales_with_degree() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&rocky, "Sports");
+
+        let raid_query = vec![RecommenderNode::Object(raid.clone())];
+        let rocky_query = vec![RecommenderNode::Object(rocky.clone())];
+        let both_query = vec![
+            RecommenderNode::Object(raid),
+            RecommenderNode::Object(rocky),
+        ];
+
+        assert_eq!(recommender.degree_budget(&raid_query, 100), 100);
+        assert_eq!(recommender.degree_budget(&rocky_query, 100), 300);
+        assert_eq!(recommender.degree_budget(&both_query, 100), 400);
+        assert_eq!(
+            recommender.degree_budget(&[RecommenderNode::Tag(String::from("Unknown"))], 100),
+            0
+        );
+    }
+
+    #[test]
+    fn recommendations_within_allowed_objects() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let creed = String::from("Creed");
+        let python = String::from("Monty Python and The Holy Grail");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&creed, "Drama");
+        recommender.tag_object(&python, "Comedy");
+
+        let mut allowed: HashSet<String> = HashSet::new();
+        allowed.insert(raid.clone());
+        allowed.insert(creed.clone());
+        allowed.insert(python.clone());
+
+        let unrestricted = recommender.object_recommendations(
+            std::slice::from_ref(&raid),
+            10,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(unrestricted[0], rocky);
+
+        let recommendations = recommender.object_recommendations_within(
+            std::slice::from_ref(&raid),
+            &allowed,
+            10,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations, vec![creed]);
+    }
+
+    #[test]
+    fn approximate_top_k_matches_exact_top_k() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+
+        for obj in 0..200 {
+            recommender.tag_object(&obj, "Popular");
+        }
+
+        let weight = |obj: &u32| 1.0 / ((obj + 1) as f32).powi(2);
+        let top_k = |counts: &HashMap<RecommenderNode<u32>, u32>, k: usize| {
+            let mut ranked = counts
+                .iter()
+                .filter(|(k, _)| **k != RecommenderNode::Object(0))
+                .collect::<Vec<(&RecommenderNode<u32>, &u32)>>();
+            ranked.sort_by_key(|(_, v)| **v);
+            ranked
+                .iter()
+                .rev()
+                .take(k)
+                .map(|(k, _)| (*k).clone())
+                .collect::<HashSet<RecommenderNode<u32>>>()
+        };
+        let weight_fun = |from: &RecommenderNode<u32>, to: &RecommenderNode<u32>| {
+            bipartite_weight(from, to, |_, _| 1.0, |_, obj| weight(obj))
+        };
+
+        let exact =
+            recommender.recommendations_map(&RecommenderNode::Object(0), 21, 20000, weight_fun);
+        let approximate = recommender.approximate_recommendations_map(
+            &RecommenderNode::Object(0),
+            21,
+            20000,
+            weight_fun,
+            &SketchConfig::new(128, 4, 20),
+        );
+
+        assert!(exact.len() > 40);
+        assert!(approximate.len() <= 20);
+        let exact_top = top_k(&exact, 5);
+        let approximate_top = top_k(&approximate, 10);
+        assert!(
+            exact_top.is_subset(&approximate_top),
+            "{:?} is not contained in {:?}",
+            exact_top,
+            approximate_top
+        );
+    }
+
+    #[test]
+    fn recommendations_from_slices() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+
+        let from_array = [raid.clone()];
+        let recommendations =
+            recommender.object_recommendations(&from_array, 3, 100, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(recommendations, vec![rocky.clone()]);
+
+        let recommendations = recommender.recommendations(
+            &[RecommenderNode::Object(raid)],
+            3,
+            100,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    }
+
+    #[test]
+    fn subset_recommends_within_tags() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let creed = String::from("Creed");
+        let python = String::from("Monty Python and The Holy Grail");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&creed, "Drama");
+        recommender.tag_object(&python, "Comedy");
+        recommender.add_tag("Documentary");
+
+        let subset = recommender.clone_subset(&[
+            String::from("Action"),
+            String::from("Documentary"),
+            String::from("Unknown"),
+        ]);
+
+        assert!(subset
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Documentary"))));
+        assert!(!subset
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Unknown"))));
+        assert!(!subset
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Drama"))));
+        assert!(!subset.graph.contains(&RecommenderNode::Object(creed)));
+        assert_eq!(
+            subset.graph.degree(&RecommenderNode::Object(rocky.clone())),
+            1
+        );
+
+        let recommendations = subset.recommendations(
+            &[RecommenderNode::Object(raid)],
+            10,
+            100,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations.len(), 2);
+        assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+        assert!(recommendations.contains(&RecommenderNode::Tag(String::from("Action"))));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn async_recommendations() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+
+        let queries = vec![RecommenderNode::Object(raid)];
+        let sync_recommendations =
+            recommender.recommendations(&queries, 3, 1000, |_, _| 1.0, |_, _| 1.0);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let async_recommendations = runtime.block_on(Arc::new(recommender).recommendations_async(
+            queries,
+            3,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        ));
+
+        assert_eq!(async_recommendations, sync_recommendations);
+        assert_eq!(
+            async_recommendations,
+            vec![
+                RecommenderNode::Tag(String::from("Action")),
+                RecommenderNode::Object(rocky)
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_tags_boost_equally_visited_objects() {
+        let query = RecommenderNode::Object(String::from("query"));
+        let two_tags = RecommenderNode::Object(String::from("two tags"));
+        let one_tag = RecommenderNode::Object(String::from("one tag"));
+        let action = RecommenderNode::Tag(String::from("Action"));
+        let drama = RecommenderNode::Tag(String::from("Drama"));
+
+        let mut tagged_visits: HashMap<RecommenderNode<String>, TaggedVisits> = HashMap::new();
+        let walks = vec![
+            vec![query.clone(), action.clone(), two_tags.clone()],
+            vec![query.clone(), drama.clone(), two_tags.clone()],
+            vec![query.clone(), action.clone(), one_tag.clone()],
+            vec![query.clone(), action.clone(), one_tag.clone()],
+        ];
+        for walk in walks {
+            Recommender::record_tagged_walk(&mut tagged_visits, walk.into_iter().rev().collect());
+        }
+
+        assert_eq!(tagged_visits[&two_tags].visits, 2);
+        assert_eq!(tagged_visits[&one_tag].visits, 2);
+        assert_eq!(tagged_visits[&two_tags].via_tags.len(), 2);
+        assert_eq!(tagged_visits[&one_tag].via_tags.len(), 1);
+        assert!(tagged_visits[&query].via_tags.is_empty());
+        assert!(tagged_visits[&action].via_tags.is_empty());
+
+        let unboosted = Recommender::tag_boosted_counts(&tagged_visits, 0.0);
+        assert_eq!(unboosted[&two_tags], unboosted[&one_tag]);
+
+        let boosted = Recommender::tag_boosted_counts(&tagged_visits, 0.5);
+        assert!(boosted[&two_tags] > boosted[&one_tag]);
+        assert_eq!(boosted[&one_tag], 2.0);
+        assert_eq!(boosted[&two_tags], 3.0);
+    }
+
+    #[test]
+    fn per_query_weights() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let die_hard = String::from("Die Hard");
+        let python = String::from("Monty Python and The Holy Grail");
+        let airplane = String::from("Airplane!");
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&die_hard, "Action");
+        recommender.tag_object(&python, "Comedy");
+        recommender.tag_object(&airplane, "Comedy");
+
+        let mut ratings: HashMap<String, f32> = HashMap::new();
+        ratings.insert(raid.clone(), 1.0);
+        ratings.insert(rocky.clone(), 1.0);
+        let rated = |_: &String, obj: &String| *ratings.get(obj).unwrap_or(&0.0);
+
+        let recommendations = recommender.recommendations_per_query(
+            &[
+                (
+                    RecommenderNode::Object(raid.clone()),
+                    WeightPair::new(|_, _| 1.0, rated),
+                ),
+                (
+                    RecommenderNode::Tag(String::from("Comedy")),
+                    WeightPair::uniform(),
+                ),
+            ],
+            5,
+            1000,
+        );
+
+        assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+        assert!(recommendations.contains(&RecommenderNode::Object(python.clone())));
+        assert!(recommendations.contains(&RecommenderNode::Object(airplane.clone())));
+        assert!(!recommendations.contains(&RecommenderNode::Object(die_hard)));
+        assert!(!recommendations.contains(&RecommenderNode::Object(raid.clone())));
+
+        let rated_comedies = recommender.recommendations_per_query(
+            &[(
+                RecommenderNode::Tag(String::from("Comedy")),
+                WeightPair::new(|_, _| 1.0, rated),
+            )],
+            5,
+            1000,
+        );
+        assert!(rated_comedies.is_empty());
+    }
+
+    #[test]
+    fn suggest_tags_from_neighbors() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let query = String::from("Action Drama");
+        let action_thriller = String::from("Action Thriller");
+        let drama_thriller = String::from("Drama Thriller");
+        let action_comedy = String::from("Action Comedy");
+
+        recommender.tag_object(&query, "Action");
+        recommender.tag_object(&query, "Drama");
+        recommender.tag_object(&action_thriller, "Action");
+        recommender.tag_object(&action_thriller, "Thriller");
+        recommender.tag_object(&drama_thriller, "Drama");
+        recommender.tag_object(&drama_thriller, "Thriller");
+        recommender.tag_object(&action_comedy, "Action");
+        recommender.tag_object(&action_comedy, "Comedy");
+
+        let suggestions = recommender.suggest_tags(&query, 10, 4, 4000, |_, _| 1.0, |_, _| 1.0);
+
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].0, "Thriller");
+        assert_eq!(suggestions[1].0, "Comedy");
+        assert!(suggestions[0].1 > suggestions[1].1);
+
+        let limited = recommender.suggest_tags(&query, 1, 4, 100, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn exclude_high_degree_candidates() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+
+        let tags = ["Action", "Comedy", "Drama", "Horror", "Romance"];
+        for tag in tags.iter() {
+            recommender.tag_object(&0, tag);
+        }
+        recommender.tag_object(&1, "Action");
+        recommender.tag_object(&2, "Action");
+        recommender.tag_object(&2, "Drama");
+        recommender.tag_object(&3, "Horror");
+
+        let unrestricted =
+            recommender.object_recommendations(&[1], 10, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(unrestricted[0], 0);
+
+        let recommendations = recommender.object_recommendations_max_degree(
+            &[1],
+            4,
+            10,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(!recommendations.contains(&0));
+        assert!(recommendations.contains(&2));
+        assert!(recommendations.contains(&3));
+
+        let recommendations = recommender.object_recommendations_max_degree(
+            &[1],
+            5,
+            10,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(recommendations.contains(&0));
+    }
+
+    #[test]
+    fn adaptive_early_stopping_uses_fewer_steps() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..40 {
+            recommender.tag_object(&obj, "Hub");
+        }
+
+        let query = RecommenderNode::Object(0);
+        let steps_until_threshold = |config: &EarlyStopping| -> usize {
+            (0..20)
+                .map(|_| {
+                    let (counts, steps) = recommender.early_stopping_recommendations_map(
+                        &query,
+                        3,
+                        100000,
+                        |_, _| 1.0,
+                        config,
+                    );
+                    let candidates = counts
+                        .iter()
+                        .filter(|(node, count)| **node != query && **count >= 3)
+                        .count();
+                    assert!(candidates >= 20);
+                    steps
+                })
+                .sum()
+        };
+
+        let fixed = steps_until_threshold(&EarlyStopping::new(3, 20));
+        let adaptive = steps_until_threshold(&EarlyStopping::new(3, 20).adaptive(true));
+        assert!(adaptive < fixed);
+    }
+
+    #[test]
+    fn early_stopping_on_dense_component() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..30 {
+            for tag in 0..5 {
+                recommender.tag_object(&obj, &format!("{}", tag));
+            }
+        }
+        let query = RecommenderNode::Object(0);
+        let budget = 1_000_000;
+
+        let (counts, steps) = recommender.early_stopping_recommendations_map(
+            &query,
+            3,
+            budget,
+            |_, _| 1.0,
+            &EarlyStopping::new(4, 10),
+        );
+        assert!(steps < budget / 100);
+        let candidates = counts
+            .iter()
+            .filter(|(node, count)| **node != query && **count >= 4)
+            .count();
+        assert!(candidates >= 10);
+
+        // Only objects count, even though tags are visited on every walk
+        let (counts, object_steps) = recommender.early_stopping_recommendations_map_with(
+            &query,
+            3,
+            budget,
+            |_, _| 1.0,
+            &EarlyStopping::new(4, 5),
+            |node| matches!(node, RecommenderNode::Object(_)),
+        );
+        assert!(object_steps < budget / 100);
+        let object_candidates = counts
+            .iter()
+            .filter(|(node, count)| **node != query && node.as_object().is_some() && **count >= 4)
+            .count();
+        assert!(object_candidates >= 5);
+
+        let recommendations = recommender.object_recommendations_early_stopping(
+            &[0],
+            3,
+            budget,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &EarlyStopping::new(4, 5),
+        );
+        assert!(!recommendations.is_empty() && !recommendations.contains(&0));
+    }
+
+    #[test]
+    fn group_recommendations_by_tag() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let query = String::from("Query");
+        recommender.tag_object(&query, "Action");
+        recommender.tag_object(&query, "Drama");
+        for movie in ["The Raid", "Rocky"].iter() {
+            recommender.tag_object(&String::from(*movie), "Action");
+        }
+        for movie in ["Creed", "Titanic"].iter() {
+            recommender.tag_object(&String::from(*movie), "Drama");
+        }
+
+        let groups = recommender.object_recommendations_grouped_by_tag(
+            std::slice::from_ref(&query),
+            3,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+
+        assert_eq!(groups.len(), 2);
+        let action: HashSet<&String> = groups["Action"].iter().collect();
+        let drama: HashSet<&String> = groups["Drama"].iter().collect();
+        assert_eq!(action.len(), 2);
+        assert!(action.contains(&String::from("The Raid")));
+        assert!(action.contains(&String::from("Rocky")));
+        assert_eq!(drama.len(), 2);
+        assert!(drama.contains(&String::from("Creed")));
+        assert!(drama.contains(&String::from("Titanic")));
+    }
+
+    #[test]
+    fn min_query_coverage() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for (obj, tag) in [
+            (1, "A"),
+            (2, "B"),
+            (3, "C"),
+            (10, "A"),
+            (10, "B"),
+            (11, "A"),
+        ]
+        .iter()
+        {
+            recommender.tag_object(obj, tag);
+        }
+        recommender.tag_object(&12, "C");
+        let queries = [
+            RecommenderNode::Object(1),
+            RecommenderNode::Object(2),
+            RecommenderNode::Object(3),
+        ];
+
+        let recommendations = recommender.recommendations_with_min_coverage(
+            &queries,
+            3,
+            3000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            1,
+        );
+        assert!(recommendations.contains(&RecommenderNode::Object(11)));
+        assert!(recommendations.contains(&RecommenderNode::Object(12)));
+
+        let recommendations = recommender.recommendations_with_min_coverage(
+            &queries,
+            3,
+            3000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            2,
+        );
+        assert_eq!(recommendations, vec![RecommenderNode::Object(10)]);
+    }
+
+    #[test]
+    fn borrowed_object_recommendations() {
+        let mut recommender: Recommender<String> = Recommender::new();
+        for movie in ["Query", "Low", "Mid", "High"].iter() {
+            recommender.tag_object(&String::from(*movie), "Action");
+        }
+        let tag_to_object_weight = |_: &String, obj: &String| match obj.as_str() {
+            "Low" => 1.0,
+            "Mid" => 10.0,
+            "High" => 100.0,
+            _ => 0.0,
+        };
+        let queries = [String::from("Query")];
+
+        let owned =
+            recommender.object_recommendations(&queries, 3, 3000, |_, _| 1.0, tag_to_object_weight);
+        let borrowed = recommender
+            .object_recommendations_iter(&queries, 3, 3000, |_, _| 1.0, tag_to_object_weight)
+            .collect::<Vec<&String>>();
+
+        assert_eq!(owned, vec!["High", "Mid", "Low"]);
+        assert_eq!(borrowed, owned.iter().collect::<Vec<&String>>());
+        for obj in borrowed {
+            let stored = recommender
+                .graph
+                .stored_node(&RecommenderNode::Object(obj.clone()));
+            match stored {
+                Some(RecommenderNode::Object(stored)) => assert!(std::ptr::eq(stored, obj)),
+                _ => panic!("{} is not stored in the graph", obj),
+            }
+        }
+    }
+
+    #[test]
+    fn ignore_edges_before_cutoff() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object_at(&0, "Old", 10);
+        recommender.tag_object_at(&0, "New", 100);
+        for obj in 1..5 {
+            recommender.tag_object_at(&obj, "Old", 10);
+        }
+        for obj in 5..10 {
+            recommender.tag_object_at(&obj, "New", 100);
+        }
+        recommender.tag_object_at(&1, "New", 20);
+
+        let queries = [RecommenderNode::Object(0)];
+        let recommendations =
+            recommender.recommendations(&queries, 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(recommendations.contains(&RecommenderNode::Object(1)));
+
+        let recommendations =
+            recommender.recommendations_since(&queries, 50, 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.is_empty());
+        for node in recommendations {
+            match node {
+                RecommenderNode::Object(obj) => assert!(obj >= 5, "{} was recommended", obj),
+                RecommenderNode::Tag(tag) => assert_eq!(tag, "New"),
+            }
+        }
+
+        let subset = recommender.clone_subset(&[String::from("New")]);
+        assert_eq!(subset.edge_timestamp(&1, "New"), 20);
+    }
+
+    #[test]
+    fn top_degree_first_hop() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, "Action");
+        }
+        for tag in ["Drama", "Comedy", "Thriller"].iter() {
+            recommender.tag_object(&0, tag);
+        }
+        let queries = [RecommenderNode::Tag(String::from("Action"))];
+        let object_visits = |strategy: FirstHopStrategy| -> Vec<u32> {
+            recommender
+                .recommendations_with_first_hop(&queries, 2, 2000, |_, _| 1.0, |_, _| 1.0, strategy)
+                .into_iter()
+                .flat_map(|node| match node {
+                    RecommenderNode::Object(obj) => Some(obj),
+                    RecommenderNode::Tag(_) => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(object_visits(FirstHopStrategy::TopDegree), vec![0]);
+        assert_eq!(object_visits(FirstHopStrategy::Uniform).len(), 10);
+        assert_eq!(object_visits(FirstHopStrategy::Weighted).len(), 10);
+    }
+
+    #[test]
+    fn hard_block_bridge_tag() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for (obj, tag) in [
+            (1, "A"),
+            (2, "A"),
+            (2, "Bridge"),
+            (3, "Bridge"),
+            (3, "B"),
+            (4, "B"),
+        ]
+        .iter()
+        {
+            recommender.tag_object(obj, tag);
+        }
+        let queries = [RecommenderNode::Object(1)];
+
+        let recommendations =
+            recommender.recommendations(&queries, 20, 2000, |_, _| 1.0, |_, _| 1.0);
+        assert!(recommendations.contains(&RecommenderNode::Object(4)));
+
+        let mut blocked = HashSet::new();
+        blocked.insert(RecommenderNode::Tag(String::from("Bridge")));
+        let recommendations = recommender.recommendations_with_hard_block(
+            &queries,
+            &blocked,
+            20,
+            2000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations.len(), 2);
+        assert!(recommendations.contains(&RecommenderNode::Object(2)));
+        assert!(recommendations.contains(&RecommenderNode::Tag(String::from("A"))));
+    }
+
+    #[test]
+    fn seeded_object_recommendations() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..30 {
+            recommender.tag_object(&obj, &format!("{}", obj % 4));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+
+        let rng = StdRng::seed_from_u64(42);
+        let first = recommender.object_recommendations_seeded(
+            &[0, 1],
+            5,
+            500,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut rng.clone(),
+        );
+        let second = recommender.object_recommendations_seeded(
+            &[0, 1],
+            5,
+            500,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut rng.clone(),
+        );
+
+        assert!(first.len() > 10);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn precomputed_degrees() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, if obj < 5 { "Low" } else { "High" });
+        }
+        recommender.tag_object(&0, "High");
+        let queries = [RecommenderNode::Object(0), RecommenderNode::Object(9)];
+        let queries_with_degrees = queries
+            .iter()
+            .map(|q| (q.clone(), recommender.graph.degree(q)))
+            .collect::<Vec<(RecommenderNode<u32>, usize)>>();
+
+        assert_eq!(
+            recommender.degree_budgets(queries_with_degrees.iter().map(|(_, d)| *d), 1000),
+            recommender.query_budgets(&queries, 1000)
+        );
+
+        let expected: HashSet<RecommenderNode<u32>> = recommender
+            .recommendations(&queries, 3, 2000, |_, _| 1.0, |_, _| 1.0)
+            .into_iter()
+            .collect();
+        let recommendations: HashSet<RecommenderNode<u32>> = recommender
+            .recommendations_with_degrees(&queries_with_degrees, 3, 2000, |_, _| 1.0, |_, _| 1.0)
+            .into_iter()
+            .collect();
+        assert_eq!(recommendations, expected);
+    }
+
+    #[test]
+    fn tags_only_accumulation() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, if obj % 2 == 0 { "Even" } else { "Odd" });
+            recommender.tag_object(&obj, "Number");
+        }
+
+        let counts = recommender.recommendations_map_with_mode(
+            &RecommenderNode::Object(0),
+            10,
+            1000,
+            |_, _| 1.0,
+            AccumulationMode::TagsOnly,
+        );
+        assert_eq!(counts.len(), 3);
+        assert!(counts.keys().all(|node| match node {
+            RecommenderNode::Tag(_) => true,
+            RecommenderNode::Object(_) => false,
+        }));
+
+        let counts = recommender.recommendations_map_with_mode(
+            &RecommenderNode::Object(0),
+            10,
+            1000,
+            |_, _| 1.0,
+            AccumulationMode::All,
+        );
+        assert!(counts.contains_key(&RecommenderNode::Object(1)));
+    }
+
+    #[test]
+    fn shrink_recommender() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..500 {
+            recommender.tag_object_at(&obj, "Number", u64::from(obj));
+        }
+        recommender = recommender.clone_subset(&[String::from("Number")]);
+        recommender.shrink_to_fit();
+
+        assert!(recommender.capacity() >= 501);
+        assert_eq!(recommender.edge_timestamp(&10, "Number"), 10);
+        let recommendations =
+            recommender.object_recommendations(&[0], 3, 100, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.is_empty());
+    }
+
+    #[test]
+    fn query_relative_weights() {
+        let mut recommender: Recommender<&str> = Recommender::new();
+        for obj in ["left query", "right query", "left", "right"].iter() {
+            recommender.tag_object(obj, "Shared");
+        }
+        let tag_to_object_weight =
+            |query: &RecommenderNode<&str>, _: &String, obj: &&str| match query {
+                RecommenderNode::Object(query) if !query.starts_with(obj) => 0.0,
+                _ => 1.0,
+            };
+
+        for (query, same_side, other_side) in [
+            ("left query", "left", "right"),
+            ("right query", "right", "left"),
+        ]
+        .iter()
+        {
+            let recommendations = recommender.recommendations_with_query_weights(
+                &[RecommenderNode::Object(*query)],
+                3,
+                500,
+                |_, _, _| 1.0,
+                tag_to_object_weight,
+            );
+            assert!(recommendations.contains(&RecommenderNode::Object(*same_side)));
+            assert!(!recommendations.contains(&RecommenderNode::Object(*other_side)));
+        }
+    }
+
+    #[test]
+    fn remove_isolated_objects_and_tags() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&1, "Odd");
+        recommender.tag_object(&3, "Odd");
+        recommender.add_object(&2);
+        recommender.add_tag("Even");
+
+        assert_eq!(recommender.remove_isolated(), 2);
+        assert!(recommender.graph.contains(&RecommenderNode::Object(1)));
+        assert!(recommender.graph.contains(&RecommenderNode::Object(3)));
+        assert!(!recommender.graph.contains(&RecommenderNode::Object(2)));
+        assert!(!recommender
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Even"))));
+    }
+
+    #[test]
+    fn recommender_node_conversions() {
+        let object: RecommenderNode<String> = RecommenderNode::from(String::from("Rocky"));
+        assert_eq!(object, RecommenderNode::Object(String::from("Rocky")));
+        assert_eq!(object.as_object(), Some(&String::from("Rocky")));
+        assert_eq!(object.as_tag(), None);
+
+        let tag: RecommenderNode<String> = RecommenderNode::tag("Rocky");
+        assert_eq!(tag, RecommenderNode::Tag(String::from("Rocky")));
+        assert_eq!(tag.as_object(), None);
+        assert_eq!(tag.as_tag(), Some("Rocky"));
+        assert_ne!(object, tag);
+
+        let nodes: Vec<RecommenderNode<u32>> = vec![1.into(), 2.into()];
+        assert_eq!(
+            nodes.iter().flat_map(|node| node.as_object()).sum::<u32>(),
+            3
+        );
+    }
+
+    #[test]
+    fn freshness_boost() {
+        let mut recommender: Recommender<&str> = Recommender::new();
+        recommender.tag_object(&"query", "Action");
+        recommender.tag_object(&"old", "Action");
+        recommender.tag_object(&"new", "Action");
+        recommender.tag_object(&"old", "Action");
+
+        assert_eq!(recommender.insertion_order[&"old"], 1);
+        assert_eq!(recommender.insertion_order[&"new"], 2);
+
+        for _ in 0..5 {
+            let recommendations = recommender.object_recommendations_with_freshness(
+                &["query"],
+                3,
+                30000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                1.0,
+            );
+            assert_eq!(recommendations, vec!["new", "old"]);
+        }
+
+        // The multiplier applies to the final score, even below 1
+        let mut scores: HashMap<RecommenderNode<&str>, f64> = HashMap::new();
+        scores.insert(RecommenderNode::Object("new"), 0.55);
+        scores.insert(RecommenderNode::Object("old"), 0.6);
+        scores.insert(RecommenderNode::tag("Action"), 0.6);
+        recommender.apply_freshness(&mut scores, 1.0);
+        let ranked = recommender.rank_scored_recommendations(&scores, |_| true);
+        assert_eq!(ranked[0].0, RecommenderNode::Object("new"));
+        assert!((ranked[0].1 - 0.3025 * 2.0).abs() < 1e-9);
+        assert!((ranked[1].1 - 0.36 * 1.5).abs() < 1e-9);
+        assert_eq!(ranked[2], (RecommenderNode::tag("Action"), 0.36));
+    }
+
+    #[test]
+    fn walk_aggregation() {
+        let mut walks: Vec<LinkedList<&str>> = (0..10)
+            .map(|_| vec!["query", "consistent"].into_iter().collect())
+            .collect();
+        walks[0].extend(std::iter::repeat_n("spiking", 50));
+
+        let aggregate = |aggregation: WalkAggregation| {
+            let mut acc: HashMap<&str, u32> = HashMap::new();
+            for walk in walks.iter() {
+                aggregation.record(&mut acc, walk.clone());
+            }
+            acc
+        };
+
+        let sum = aggregate(WalkAggregation::Sum);
+        assert_eq!(sum["consistent"], 10);
+        assert_eq!(sum["spiking"], 50);
+
+        let max = aggregate(WalkAggregation::Max);
+        assert_eq!(max["consistent"], 1);
+        assert_eq!(max["spiking"], 50);
+
+        let visit_rate = aggregate(WalkAggregation::VisitRate);
+        assert_eq!(visit_rate["consistent"], 10);
+        assert_eq!(visit_rate["spiking"], 1);
+        assert!(visit_rate["consistent"] > visit_rate["spiking"]);
+    }
+
+    #[test]
+    fn tag_cooccurrence() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let python = String::from("Monty Python and The Holy Grail");
+        let tag = |name: &str| String::from(name);
+
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&raid, "Martial Arts");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&rocky, "Sports");
+        recommender.tag_object(&python, "Comedy");
+        recommender.add_tag("Horror");
+
+        let cooccurrence = recommender.tag_cooccurrence();
+
+        assert_eq!(cooccurrence[&(tag("Action"), tag("Drama"))], 1);
+        assert_eq!(cooccurrence[&(tag("Action"), tag("Martial Arts"))], 1);
+        assert_eq!(cooccurrence[&(tag("Action"), tag("Sports"))], 1);
+        assert_eq!(cooccurrence[&(tag("Drama"), tag("Sports"))], 1);
+        assert_eq!(cooccurrence.len(), 4);
+        assert!(!cooccurrence.contains_key(&(tag("Drama"), tag("Action"))));
+
+        recommender.tag_object(&python, "Drama");
+        recommender.tag_object(&python, "Sports");
+        assert_eq!(
+            recommender.tag_cooccurrence()[&(tag("Drama"), tag("Sports"))],
+            2
+        );
+    }
+
+    #[test]
+    fn sanitized_recommendations_never_panic() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..50 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+
+        let adversarial = [
+            f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::MAX,
+            f32::MIN,
+            f32::MIN_POSITIVE,
+            f32::EPSILON,
+            -1.0,
+            0.0,
+            1.0e30,
+        ];
+        let mut rng = StdRng::seed_from_u64(479);
+        for _ in 0..50 {
+            let weights = (0..64)
+                .map(|_| adversarial[rng.gen_range(0, adversarial.len())])
+                .collect::<Vec<f32>>();
+            let weight =
+                |obj: &u32, tag: &String| weights[(*obj as usize + tag.len() * 13) % weights.len()];
+            let recommendations = recommender.object_recommendations_sanitized(
+                &[0, 1],
+                5,
+                200,
+                weight,
+                |tag, obj| weight(obj, tag),
+            );
+            let unique = recommendations.iter().collect::<HashSet<&u32>>();
+            assert_eq!(unique.len(), recommendations.len());
+            assert!(recommendations.iter().all(|obj| *obj < 50 && *obj > 1));
+        }
+
+        // A query without edges doesn't empty the results of the others
+        recommender.add_object(&100);
+        for invalid in &adversarial[..3] {
+            let recommendations = recommender.object_recommendations_sanitized(
+                &[0, 100],
+                5,
+                1000,
+                |obj, _| if *obj == 0 { *invalid } else { 1.0 },
+                |_, _| 1.0,
+            );
+            assert!(recommendations.is_empty());
+            let recommendations = recommender.object_recommendations_sanitized(
+                &[0, 100],
+                5,
+                1000,
+                |_, _| 1.0,
+                |_, obj| if *obj % 2 == 0 { *invalid } else { 1.0 },
+            );
+            assert!(!recommendations.is_empty());
+            assert!(recommendations.iter().all(|obj| *obj % 2 == 1));
+        }
+    }
+
+    #[test]
+    fn confidence_scales_contributions() {
+        let mut recommender: Recommender<&str> = Recommender::new();
+        recommender.tag_object(&"seed a", "a");
+        recommender.tag_object(&"candidate a", "a");
+        recommender.tag_object(&"seed b", "b");
+        recommender.tag_object(&"candidate b", "b");
+
+        // Every walk goes from the seed to its candidate, so the counts
+        // are always the same.
+        let weight_fun = |from: &RecommenderNode<&str>, to: &RecommenderNode<&str>| match to {
+            RecommenderNode::Object(obj) if obj.starts_with("seed") => 0.0,
+            _ => bipartite_weight(from, to, |_, _| 1.0, |_, _| 1.0),
+        };
+        let scores = |confidence_b: f64| {
+            recommender.confidence_weighted_recommendations(
+                &[
+                    (RecommenderNode::Object("seed a"), 1.0),
+                    (RecommenderNode::Object("seed b"), confidence_b),
+                ],
+                3,
+                600,
+                weight_fun,
+            )
+        };
+
+        let full = scores(1.0);
+        let half = scores(0.5);
+        let candidate_a = RecommenderNode::Object("candidate a");
+        let candidate_b = RecommenderNode::Object("candidate b");
+        assert_eq!(full[&candidate_a], 10.0);
+        assert_eq!(full[&candidate_b], 10.0);
+        assert_eq!(half[&candidate_a], full[&candidate_a]);
+        assert_eq!(half[&candidate_b], full[&candidate_b] / 2.0);
+
+        assert_eq!(scores(f64::NAN)[&candidate_b], 0.0);
+        assert_eq!(scores(2.0)[&candidate_b], full[&candidate_b]);
+    }
+
+    #[test]
+    fn live_blocklist() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..5 {
+            recommender.tag_object(&obj, "Numbers");
+        }
+        let blocklist: Arc<RwLock<HashSet<u32>>> = Arc::new(RwLock::new(HashSet::new()));
+        blocklist.write().unwrap().insert(1);
+        recommender.set_blocklist(blocklist.clone());
+
+        let recommendations =
+            recommender.object_recommendations(&[0], 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.contains(&1));
+        assert!(recommendations.contains(&2));
+
+        blocklist.write().unwrap().insert(2);
+        let recommendations =
+            recommender.object_recommendations(&[0], 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.contains(&1));
+        assert!(!recommendations.contains(&2));
+        assert_eq!(recommendations.len(), 2);
+
+        // Every other ranking excludes the blocked objects as well
+        {
+            use self::memory_budget::BudgetPolicy;
+            use self::memory_budget::MemoryBudget;
+            use self::pagination::PageCursor;
+            use rand::rngs::StdRng;
+            use rand::SeedableRng;
+            use std::time::Duration;
+
+            let o2t = |_: &u32, _: &String| 1.0;
+            let t2o = |_: &String, _: &u32| 1.0;
+            let queries = [RecommenderNode::Object(0)];
+            let unblocked = |objects: Vec<u32>| {
+                assert!(!objects.is_empty());
+                !objects.contains(&1) && !objects.contains(&2)
+            };
+            let unblocked_nodes = |nodes: Vec<RecommenderNode<u32>>| {
+                unblocked(
+                    nodes
+                        .into_iter()
+                        .flat_map(|node| node.as_object().cloned())
+                        .collect(),
+                )
+            };
+
+            assert!(unblocked(recommender.object_recommendations_filtered(
+                &[0],
+                3,
+                1000,
+                o2t,
+                t2o,
+                |_| true
+            )));
+            assert!(unblocked(
+                recommender
+                    .object_recommendations_iter(&[0], 3, 1000, o2t, t2o)
+                    .cloned()
+                    .collect()
+            ));
+            assert!(unblocked(
+                recommender
+                    .object_recommendations_rich(&[0], 3, 1000, o2t, t2o)
+                    .into_iter()
+                    .map(|(obj, _)| obj)
+                    .collect()
+            ));
+            assert!(unblocked(recommender.object_recommendations_seeded(
+                &[0],
+                3,
+                1000,
+                o2t,
+                t2o,
+                &mut StdRng::seed_from_u64(483)
+            )));
+            assert!(unblocked(recommender.object_recommendations_within(
+                &[0],
+                &(0..5).collect(),
+                3,
+                1000,
+                o2t,
+                t2o
+            )));
+            assert!(unblocked(recommender.object_recommendations_within_tag(
+                &0, "Numbers", 3, 1000, o2t, t2o
+            )));
+            assert!(unblocked(recommender.object_recommendations_max_degree(
+                &[0],
+                10,
+                3,
+                1000,
+                o2t,
+                t2o
+            )));
+            assert!(unblocked(
+                recommender
+                    .object_recommendations_grouped_by_tag(&[0], 3, 1000, o2t, t2o)
+                    .into_values()
+                    .flatten()
+                    .collect()
+            ));
+            assert!(unblocked(
+                recommender.object_recommendations_with_freshness(&[0], 3, 1000, o2t, t2o, 1.0)
+            ));
+            assert!(unblocked(recommender.object_recommendations_topk_exact(
+                &[0],
+                10,
+                0.3,
+                o2t,
+                t2o
+            )));
+            assert!(unblocked(
+                recommender
+                    .object_recommendations_page(
+                        &[0],
+                        3,
+                        1000,
+                        &WeightPair::uniform(),
+                        &mut StdRng::seed_from_u64(483),
+                        &PageCursor::first(10)
+                    )
+                    .0
+            ));
+            assert!(unblocked(
+                recommender
+                    .similar_objects(&0, 10)
+                    .into_iter()
+                    .map(|(obj, _)| obj)
+                    .collect()
+            ));
+            assert!(unblocked_nodes(
+                recommender.recommendations_with_idf(&queries, 3, 1000, o2t, t2o)
+            ));
+            assert!(unblocked_nodes(
+                recommender.recommendations_blended(&queries, 0.5, 3, 1000, o2t, t2o)
+            ));
+            assert!(unblocked_nodes(
+                recommender.recommendations_with_time_budget(
+                    &queries,
+                    3,
+                    1000,
+                    Duration::from_secs(60),
+                    o2t,
+                    t2o
+                )
+            ));
+            assert!(unblocked_nodes(
+                recommender
+                    .recommendations_with_memory_budget(
+                        &queries,
+                        3,
+                        1000,
+                        o2t,
+                        t2o,
+                        &MemoryBudget::new(None, BudgetPolicy::Error)
+                    )
+                    .unwrap()
+            ));
+            assert!(unblocked_nodes(
+                recommender
+                    .recommendations_with_stats(&queries, 3, 1000, o2t, t2o)
+                    .0
+            ));
+            #[cfg(feature = "rayon")]
+            assert!(unblocked_nodes(
+                recommender.recommendations_parallel(&queries, 3, 1000, 483, o2t, t2o)
+            ));
+        }
+
+        blocklist.write().unwrap().clear();
+        let recommendations =
+            recommender.object_recommendations(&[0], 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(recommendations.len(), 4);
+    }
+
+    #[test]
+    fn logical_depth_counts_object_hops() {
+        let mut recommender: Recommender<&str> = Recommender::new();
+        recommender.tag_object(&"query", "1");
+        recommender.tag_object(&"one hop", "1");
+        recommender.tag_object(&"one hop", "2");
+        recommender.tag_object(&"two hops", "2");
+        recommender.tag_object(&"two hops", "3");
+        recommender.tag_object(&"three hops", "3");
+
+        let reachable = |queries: &[RecommenderNode<&'static str>], logical_depth: u8| {
+            recommender
+                .recommendations_with_logical_depth(
+                    queries,
+                    logical_depth,
+                    2000,
+                    |_, _| 1.0,
+                    |_, _| 1.0,
+                )
+                .into_iter()
+                .collect::<HashSet<RecommenderNode<&str>>>()
+        };
+
+        let from_object = [RecommenderNode::Object("query")];
+        let recommendations = reachable(&from_object, 2);
+        assert!(recommendations.contains(&RecommenderNode::Object("one hop")));
+        assert!(recommendations.contains(&RecommenderNode::Object("two hops")));
+        assert!(!recommendations.contains(&RecommenderNode::Object("three hops")));
+        assert!(!recommendations.contains(&RecommenderNode::Tag(String::from("3"))));
+
+        let recommendations = reachable(&from_object, 1);
+        assert!(recommendations.contains(&RecommenderNode::Object("one hop")));
+        assert!(!recommendations.contains(&RecommenderNode::Object("two hops")));
+
+        let recommendations = reachable(&[RecommenderNode::Tag(String::from("1"))], 2);
+        assert!(recommendations.contains(&RecommenderNode::Object("two hops")));
+        assert!(!recommendations.contains(&RecommenderNode::Object("three hops")));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    #[test]
+    fn rounded_scores() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        assert_eq!(round_score(1.23456, Some(3)), 1.235);
+        assert_eq!(round_score(1.23456, Some(0)), 1.0);
+        assert_eq!(round_score(1.23456, None), 1.23456);
+        assert_eq!(round_score(1.23456, Some(100)), 1.23456);
+
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..30 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+            recommender.tag_object(&obj, &format!("{}", obj % 3 + 10));
+        }
+        let scored = |precision: Option<u32>| {
+            recommender.object_recommendations_scored_seeded(
+                &[0],
+                5,
+                2000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                &mut StdRng::seed_from_u64(487),
+                precision,
+            )
+        };
+
+        for precision in [0, 3].iter() {
+            let recommendations = scored(Some(*precision));
+            assert!(!recommendations.is_empty());
+            let scale = 10f64.powi(*precision as i32);
+            for (_, score) in recommendations.iter() {
+                assert_eq!((score * scale).round() / scale, *score);
+            }
+            assert!(recommendations.windows(2).all(|w| w[0].1 >= w[1].1));
+            assert_eq!(scored(Some(*precision)), recommendations);
+        }
+
+        let buckets = scored(Some(0))
+            .into_iter()
+            .map(|(_, score)| score as u64)
+            .collect::<HashSet<u64>>();
+        assert!(buckets.len() < scored(Some(0)).len());
+    }
 
     #[test]
-    fn dead_end_recommendations_map() {
-        let mut recommender: Recommender<String> = Recommender::new();
+    fn seeded_ties_keep_visit_order() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
 
-        let obj_0 = String::from("0.0");
-        let tag_1 = String::from("1.0");
-        let obj_2 = String::from("2.0");
+        let mut recommender: Recommender<&str> = Recommender::new();
+        recommender.tag_object(&"query", "A");
+        recommender.tag_object(&"second", "B");
+        recommender.tag_object(&"first", "A");
+        recommender.tag_object(&"first", "B");
 
-        recommender.add_object(&obj_0);
-        recommender.add_tag(&tag_1);
-        recommender.add_object(&obj_2);
+        // Every walk is query -> A -> first -> B -> second
+        let object_to_tag = |obj: &&str, tag: &String| match (*obj, tag.as_str()) {
+            ("query", "A") | ("first", "B") => 1.0,
+            _ => 0.0,
+        };
+        let tag_to_object = |tag: &String, obj: &&str| match (tag.as_str(), *obj) {
+            ("A", "first") | ("B", "second") => 1.0,
+            _ => 0.0,
+        };
+        for precision in [None, Some(0)].iter() {
+            let scored = recommender.object_recommendations_scored_seeded(
+                &["query"],
+                5,
+                500,
+                object_to_tag,
+                tag_to_object,
+                &mut StdRng::seed_from_u64(487),
+                *precision,
+            );
+            assert_eq!(scored, vec![("first", 100.0), ("second", 100.0)]);
+        }
+        let recommendations = recommender.object_recommendations_seeded(
+            &["query"],
+            5,
+            500,
+            object_to_tag,
+            tag_to_object,
+            &mut StdRng::seed_from_u64(487),
+        );
+        assert_eq!(recommendations, vec!["first", "second"]);
+    }
 
-        recommender.tag_object(&obj_0, &tag_1);
-        recommender.tag_object(&obj_2, &tag_1);
+    #[test]
+    fn reuse_visit_counts_buffer() {
+        let mut recommender: Recommender<&str> = Recommender::new();
+        recommender.tag_object(&"a", "A");
+        recommender.tag_object(&"a2", "A");
+        recommender.tag_object(&"b", "B");
+        recommender.tag_object(&"b2", "B");
+        recommender.tag_object(&"b3", "B");
 
-        let recommendations = recommender.recommendations_map(
-            &RecommenderNode::Object(obj_0.clone()),
+        let mut buffer: HashMap<RecommenderNode<&str>, u32> = HashMap::new();
+        recommender.visit_counts_into(
+            &RecommenderNode::Object("a"),
             3,
+            300,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut buffer,
+        );
+        assert_eq!(buffer.values().sum::<u32>(), 300);
+        assert!(buffer[&RecommenderNode::Object("a")] >= 100);
+        assert_eq!(buffer[&RecommenderNode::Tag(String::from("A"))], 100);
+        assert!(buffer[&RecommenderNode::Object("a2")] > 0);
+        assert_eq!(buffer.len(), 3);
+
+        recommender.visit_counts_into(
+            &RecommenderNode::Object("b"),
             3,
-            |from, to| match (from, to) {
-                (RecommenderNode::Tag(tag), RecommenderNode::Object(obj)) => {
-                    obj.parse::<f32>().unwrap() - tag.parse::<f32>().unwrap()
-                }
-                (RecommenderNode::Object(obj), RecommenderNode::Tag(tag)) => {
-                    tag.parse::<f32>().unwrap() - obj.parse::<f32>().unwrap()
-                }
-                _ => 0.0,
-            },
+            150,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut buffer,
         );
+        assert_eq!(buffer.values().sum::<u32>(), 150);
+        assert_eq!(buffer[&RecommenderNode::Tag(String::from("B"))], 50);
+        assert!(!buffer.contains_key(&RecommenderNode::Object("a")));
+        assert!(!buffer.contains_key(&RecommenderNode::Tag(String::from("A"))));
+        assert_eq!(buffer.len(), 4);
+    }
 
+    #[test]
+    fn edgeless_queries_get_no_steps() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, "Numbers");
+        }
+        recommender.add_object(&100);
+        let queries = [RecommenderNode::Object(0), RecommenderNode::Object(100)];
+
+        assert_eq!(Recommender::<u32>::query_scaling_factor(0, 10.0), 0.0);
+        assert_eq!(recommender.query_budgets(&queries, 1000), vec![1000, 0]);
+        assert_eq!(recommender.query_budgets(&queries[1..], 1000), vec![0]);
         assert_eq!(
-            recommendations.get(&RecommenderNode::Tag(tag_1)).unwrap(),
-            &1
+            Recommender::<u32>::split_steps(&[0.0, 0.0], 1000),
+            vec![0, 0]
         );
         assert_eq!(
-            recommendations
-                .get(&RecommenderNode::Object(obj_2))
-                .unwrap(),
-            &1
+            Recommender::<u32>::split_steps(&[f64::INFINITY, 1.0], 1000),
+            vec![0, 0]
         );
+        assert_eq!(Recommender::<u32>::split_steps(&[f64::NAN], 1000), vec![0]);
+
+        let recommendations =
+            recommender.recommendations(&queries, 3, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(recommendations.len(), 10);
     }
+
     #[test]
-    fn basic_recommendations() {
+    fn unknown_query_policies() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, "Numbers");
+        }
+        let queries = [
+            RecommenderNode::Object(100),
+            RecommenderNode::Object(0),
+            RecommenderNode::Tag(String::from("Letters")),
+        ];
+
+        let recommendations = recommender
+            .recommendations_with_unknown_queries(
+                &queries,
+                3,
+                1000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                UnknownQueryPolicy::Skip,
+            )
+            .unwrap();
+        assert_eq!(recommendations.len(), 10);
+        assert!(!recommendations.contains(&RecommenderNode::Object(0)));
+
+        let error = recommender
+            .recommendations_with_unknown_queries(
+                &queries,
+                3,
+                1000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                UnknownQueryPolicy::Error,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.queries,
+            vec![
+                RecommenderNode::Object(100),
+                RecommenderNode::Tag(String::from("Letters"))
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "Unknown queries: [Object(100), Tag(\"Letters\")]"
+        );
+
+        let recommendations = recommender.recommendations_with_unknown_queries(
+            &queries[1..2],
+            3,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            UnknownQueryPolicy::Error,
+        );
+        assert_eq!(recommendations.unwrap().len(), 10);
+    }
+
+    #[test]
+    fn tag_weight_multiplier() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&0, "A");
+        recommender.tag_object(&0, "B");
+        for obj in 1..6 {
+            recommender.tag_object(&obj, "A");
+        }
+        for obj in 6..11 {
+            recommender.tag_object(&obj, "B");
+        }
+
+        let visits_of = |recommender: &Recommender<u32>, objects: std::ops::Range<u32>| {
+            let mut buffer = HashMap::new();
+            recommender.visit_counts_into(
+                &RecommenderNode::Object(0),
+                3,
+                10000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                &mut buffer,
+            );
+            objects
+                .map(|obj| buffer.get(&RecommenderNode::Object(obj)).unwrap_or(&0))
+                .sum::<u32>()
+        };
+
+        let (a_visits, b_visits) = (
+            visits_of(&recommender, 1..6),
+            visits_of(&recommender, 6..11),
+        );
+        assert!(b_visits * 2 > a_visits);
+
+        recommender.set_tag_weight("B", 0.1);
+        let (a_visits, b_visits) = (
+            visits_of(&recommender, 1..6),
+            visits_of(&recommender, 6..11),
+        );
+        assert!(b_visits * 4 < a_visits);
+
+        let recommendations =
+            recommender.object_recommendations(&[0], 3, 10000, |_, _| 1.0, |_, _| 1.0);
+        assert!(recommendations[..5].iter().all(|obj| *obj < 6));
+
+        recommender.set_tag_weight("B", 1.0);
+        let (a_visits, b_visits) = (
+            visits_of(&recommender, 1..6),
+            visits_of(&recommender, 6..11),
+        );
+        assert!(b_visits * 2 > a_visits);
+    }
+
+    #[test]
+    fn walk_preview() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+            recommender.tag_object(&obj, &format!("{}", obj % 4 + 10));
+        }
+
+        let query = RecommenderNode::Object(0);
+        let walks = recommender.sample_walk_preview(&query, 5, 20, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(walks.len(), 20);
+        for walk in walks.iter() {
+            assert_eq!(walk[0], query);
+            assert!(walk.len() <= 5);
+            for (i, node) in walk.iter().enumerate() {
+                assert_eq!(i % 2 == 1, matches!(node, RecommenderNode::Tag(_)));
+            }
+        }
+
+        let walks = recommender.sample_walk_preview(
+            &query,
+            5,
+            3,
+            |_, tag| if tag == "0" { 1.0 } else { 0.0 },
+            |_, _| 1.0,
+        );
+        assert!(walks
+            .iter()
+            .all(|walk| walk[1] == RecommenderNode::Tag(String::from("0"))));
+        assert!(recommender
+            .sample_walk_preview(&query, 5, 0, |_, _| 1.0, |_, _| 1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn recommendations_within_tag() {
         let mut recommender: Recommender<String> = Recommender::new();
 
-        let obj_0 = String::from("0.0");
-        let tag_1 = String::from("1.0");
-        let obj_2 = String::from("2.0");
+        let raid = String::from("The Raid");
+        let dredd = String::from("Dredd");
+        let rocky = String::from("Rocky");
+        let creed = String::from("Creed");
+        let whiplash = String::from("Whiplash");
 
-        recommender.add_object(&obj_0);
-        recommender.add_tag(&tag_1);
-        recommender.add_object(&obj_2);
+        recommender.tag_object(&raid, "Action");
+        recommender.tag_object(&dredd, "Action");
+        recommender.tag_object(&rocky, "Action");
+        recommender.tag_object(&rocky, "Drama");
+        recommender.tag_object(&creed, "Drama");
+        recommender.tag_object(&creed, "Sports");
+        recommender.tag_object(&whiplash, "Drama");
 
-        recommender.tag_object(&obj_0, &tag_1);
-        recommender.tag_object(&obj_2, &tag_1);
+        let recommendations = recommender.object_recommendations_within_tag(
+            &raid,
+            "Drama",
+            10,
+            10000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations[0], rocky);
+        assert_eq!(recommendations.len(), 3);
+        for obj in [rocky, creed, whiplash].iter() {
+            assert!(recommendations.contains(obj));
+        }
 
-        let recommendations = recommender
-            .recommendations(
-                &vec![RecommenderNode::Object(obj_0.clone())],
-                10,
-                10,
-                |from, to| to.parse::<f32>().unwrap() - from.parse::<f32>().unwrap(),
-                |from, to| to.parse::<f32>().unwrap() - from.parse::<f32>().unwrap(),
+        assert!(recommender
+            .object_recommendations_within_tag(&raid, "Comedy", 10, 10000, |_, _| 1.0, |_, _| 1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn predicate_filtered_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&0, "Popular");
+        for obj in 1..20 {
+            recommender.tag_object(&obj, "Niche");
+        }
+        recommender.tag_object(&0, "Niche");
+        // The top recommendation, which is connected through both tags
+        recommender.tag_object(&100, "Popular");
+        recommender.tag_object(&100, "Niche");
+
+        let unfiltered = recommender.object_recommendations(&[0], 3, 10000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(unfiltered[0], 100);
+
+        let recommendations = recommender.object_recommendations_filtered(
+            &[0],
+            3,
+            10000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            |obj| *obj < 100 && obj % 2 == 0,
+        );
+        assert!(!recommendations.is_empty());
+        assert!(recommendations.iter().all(|obj| *obj < 100 && obj % 2 == 0));
+        assert_eq!(recommendations.len(), 9);
+    }
+
+    #[test]
+    fn untag_object_removes_edge() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&1, "Odd");
+        recommender.tag_object(&3, "Odd");
+        recommender.tag_object_at(&5, "Odd", 10);
+        recommender.tag_object(&5, "Prime");
+
+        let tag_query = [RecommenderNode::Tag(String::from("Odd"))];
+        let recommendations =
+            recommender.recommendations(&tag_query, 2, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(recommendations.contains(&RecommenderNode::Object(5)));
+
+        recommender.untag_object(&5, "Odd");
+        recommender.untag_object(&5, "Odd");
+        recommender.untag_object(&1, "Even");
+        recommender.untag_object(&100, "Odd");
+        assert_eq!(recommender.edge_timestamp(&5, "Odd"), 0);
+        assert!(!recommender.graph.contains(&RecommenderNode::Object(100)));
+        assert!(!recommender
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Even"))));
+
+        let recommendations =
+            recommender.recommendations(&tag_query, 2, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(recommendations.len(), 2);
+        assert!(!recommendations.contains(&RecommenderNode::Object(5)));
+
+        recommender.untag_object(&5, "Prime");
+        assert!(recommender.graph.contains(&RecommenderNode::Object(5)));
+        assert!(recommender
+            .graph
+            .contains(&RecommenderNode::Tag(String::from("Prime"))));
+        assert_eq!(recommender.graph.degree(&RecommenderNode::Object(5)), 0);
+    }
+
+    #[test]
+    fn inverse_frequency_demotes_popular_objects() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, &format!("{}", obj));
+            recommender.tag_object(&100, &format!("{}", obj));
+        }
+        recommender.tag_object(&0, "Niche");
+        recommender.tag_object(&50, "Niche");
+
+        let popular = RecommenderNode::Object(100);
+        let niche = RecommenderNode::Object(50);
+        assert_eq!(recommender.object_reach(&popular), 20);
+        assert_eq!(recommender.object_reach(&niche), 1);
+        assert_eq!(recommender.object_reach(&RecommenderNode::Object(0)), 2);
+        assert_eq!(recommender.object_reach(&RecommenderNode::tag("Niche")), 2);
+
+        let mut scores: HashMap<RecommenderNode<u32>, f64> = HashMap::new();
+        scores.insert(popular.clone(), 10.0);
+        scores.insert(niche.clone(), 10.0);
+        recommender.apply_inverse_frequency(&mut scores);
+        assert!(scores[&niche] > scores[&popular]);
+        assert!(scores[&popular] >= 10.0);
+
+        // Fractional multipliers of low scores still change the ranking
+        let mut scores: HashMap<RecommenderNode<u32>, f64> = HashMap::new();
+        scores.insert(popular.clone(), 1.4);
+        scores.insert(niche.clone(), 0.5);
+        recommender.apply_inverse_frequency(&mut scores);
+        assert_eq!(
+            recommender.rank_recommendations(&scores, |_| true),
+            vec![niche.clone(), popular.clone()]
+        );
+
+        let query = [RecommenderNode::Object(0)];
+        let rank_of = |recommendations: &[RecommenderNode<u32>], node| {
+            recommendations.iter().position(|r| *r == node).unwrap()
+        };
+        let plain = recommender.recommendations(&query, 3, 20000, |_, _| 1.0, |_, _| 1.0);
+        let idf = recommender.recommendations_with_idf(&query, 3, 20000, |_, _| 1.0, |_, _| 1.0);
+        assert!(rank_of(&idf, niche.clone()) <= rank_of(&plain, niche));
+        assert!(rank_of(&idf, popular.clone()) >= rank_of(&plain, popular));
+    }
+
+    #[test]
+    fn ranking_keeps_tie_order() {
+        let nodes = (0..5)
+            .map(RecommenderNode::Object)
+            .collect::<Vec<RecommenderNode<u32>>>();
+        let scores = [1.0, 4.0, 1.0, 4.0, 2.25];
+        let ranked = Recommender::sort_recommendations(nodes.iter().zip(scores), |node| {
+            *node != RecommenderNode::Object(4)
+        });
+        assert_eq!(
+            ranked,
+            vec![
+                (RecommenderNode::Object(1), 4.0),
+                (RecommenderNode::Object(3), 4.0),
+                (RecommenderNode::Object(0), 1.0),
+                (RecommenderNode::Object(2), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn scored_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+            recommender.tag_object(&obj, &format!("{}", obj % 5 + 10));
+        }
+        let queries = [RecommenderNode::Object(0), RecommenderNode::Object(1)];
+
+        let scored = recommender.recommendations_scored(&queries, 5, 5000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!scored.is_empty());
+        assert!(scored
+            .iter()
+            .all(|(node, score)| !queries.contains(node) && *score >= 1.0));
+        for pair in scored.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+
+        let nodes = recommender.recommendations(&queries, 5, 5000, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(nodes.len(), scored.len());
+        assert!(nodes.iter().all(|node| !queries.contains(node)));
+    }
+
+    #[test]
+    fn object_tag_tie_break() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&0, "Tied");
+        recommender.tag_object(&1, "Tied");
+        recommender.tag_object(&1, "Other");
+        recommender.tag_object(&2, "Other");
+
+        let queries = [RecommenderNode::Object(0)];
+        // Every walk is 0 -> Tied -> 1, so Tied and 1 have the same score
+        let recommendations_with = |tie_break| {
+            recommender.recommendations_with_tie_break(
+                &queries,
+                3,
+                300,
+                |_, tag| if tag == "Tied" { 1.0 } else { 0.0 },
+                |_, obj| if *obj == 1 { 1.0 } else { 0.0 },
+                tie_break,
+            )
+        };
+
+        let tied = [
+            RecommenderNode::Object(1),
+            RecommenderNode::Tag(String::from("Tied")),
+        ];
+        assert_eq!(recommendations_with(TieBreak::ObjectsFirst), tied.to_vec());
+        assert_eq!(
+            recommendations_with(TieBreak::TagsFirst),
+            vec![tied[1].clone(), tied[0].clone()]
+        );
+        let unordered = recommendations_with(TieBreak::Unordered);
+        assert_eq!(unordered.len(), 2);
+        assert!(tied.iter().all(|node| unordered.contains(node)));
+    }
+
+    #[test]
+    fn tie_break_only_reorders_equal_scores() {
+        let scored = vec![
+            (RecommenderNode::Tag(String::from("Close")), 2.99),
+            (RecommenderNode::Object(1), 2.9),
+            (RecommenderNode::Tag(String::from("Tied")), 2.0),
+            (RecommenderNode::Object(2), 2.0),
+        ];
+        let sorted_with = |tie_break: TieBreak| {
+            let mut sorted = scored.clone();
+            tie_break.sort(&mut sorted);
+            sorted
+        };
+
+        let objects_first = sorted_with(TieBreak::ObjectsFirst);
+        assert_eq!(objects_first[0], scored[0]);
+        assert_eq!(objects_first[1], scored[1]);
+        assert_eq!(objects_first[2], scored[3]);
+        assert_eq!(objects_first[3], scored[2]);
+        assert_eq!(sorted_with(TieBreak::TagsFirst), scored);
+        assert_eq!(sorted_with(TieBreak::Unordered), scored);
+    }
+
+    #[test]
+    fn recommender_from_bipartite_edges() {
+        let movies = [
+            ("The Raid", "Action"),
+            ("The Raid", "Martial Arts"),
+            ("Rocky", "Action"),
+            ("Rocky", "Drama"),
+            ("Rocky", "Drama"),
+            ("Monty Python and The Holy Grail", "Comedy"),
+        ];
+
+        let mut manual: Recommender<String> = Recommender::new();
+        for (movie, tag) in movies.iter() {
+            manual.add_object(&String::from(*movie));
+            manual.add_tag(tag);
+            manual.tag_object(&String::from(*movie), tag);
+        }
+        let recommender = Recommender::from_bipartite_edges(
+            movies
+                .iter()
+                .map(|(movie, tag)| (String::from(*movie), String::from(*tag))),
+        );
+
+        let nodes = recommender.graph.nodes().collect::<HashSet<_>>();
+        assert_eq!(nodes, manual.graph.nodes().collect::<HashSet<_>>());
+        assert_eq!(nodes.len(), 7);
+        for node in nodes {
+            assert_eq!(
+                recommender.graph.successors(node),
+                manual.graph.successors(node)
+            );
+        }
+        assert_eq!(recommender.graph.max_degree(), manual.graph.max_degree());
+        assert!(recommender
+            .graph
+            .successors(&RecommenderNode::Object(String::from("Rocky")))
+            .contains(&RecommenderNode::Tag(String::from("Drama"))));
+    }
+
+    #[test]
+    fn weighted_query_budgets() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+        }
+        let a = RecommenderNode::Object(0);
+        let b = RecommenderNode::Object(1);
+        let unknown = RecommenderNode::Object(100);
+
+        let unweighted = recommender.query_budgets(&[a.clone(), b.clone()], 1000);
+        assert_eq!(
+            recommender.weighted_query_budgets(&[(a.clone(), 1.0), (b.clone(), 1.0)], 1000),
+            unweighted
+        );
+        let budgets =
+            recommender.weighted_query_budgets(&[(a.clone(), 2.0), (b.clone(), 1.0)], 900);
+        assert_eq!(budgets, vec![600, 300]);
+        let budgets = recommender.weighted_query_budgets(
+            &[
+                (a.clone(), 1.0),
+                (b.clone(), 0.0),
+                (RecommenderNode::Object(2), -1.0),
+                (RecommenderNode::Object(3), f32::NAN),
+                (unknown.clone(), 1.0),
+            ],
+            1000,
+        );
+        assert_eq!(budgets, vec![1000, 0, 0, 0, 0]);
+        assert_eq!(
+            recommender.weighted_query_budgets(&[(a.clone(), 0.0), (unknown, 1.0)], 1000),
+            vec![0, 0]
+        );
+
+        let recommendations = recommender.recommendations_weighted(
+            &[(a, 1.0), (b, -1.0)],
+            3,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(!recommendations.is_empty());
+        assert!(recommendations.iter().all(
+            |node| matches!(node, RecommenderNode::Object(obj) if obj % 2 == 0)
+                || *node == RecommenderNode::Tag(String::from("0"))
+        ));
+    }
+
+    #[test]
+    fn blended_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        recommender.tag_object(&0, "Niche");
+        recommender.tag_object(&1, "Niche");
+        // A popular object, unrelated to the seed
+        for tag in 0..10 {
+            recommender.tag_object(&100, &format!("{}", tag));
+            recommender.tag_object(&(200 + tag), &format!("{}", tag));
+        }
+
+        let seeds = [RecommenderNode::Object(0)];
+        let niche = RecommenderNode::Object(1);
+        let popular = RecommenderNode::Object(100);
+        let blend = |global_weight| {
+            recommender.recommendations_blended(
+                &seeds,
+                global_weight,
+                3,
+                1000,
+                |_, _| 1.0,
+                |_, _| 1.0,
             )
+        };
+        let position = |recommendations: &[RecommenderNode<u32>], node| {
+            recommendations.iter().position(|r| *r == node)
+        };
+
+        let personalized = blend(0.0);
+        assert!(position(&personalized, niche.clone()).is_some());
+        assert!(position(&personalized, popular.clone()).is_none());
+        assert!(personalized.iter().all(|node| !seeds.contains(node)));
+
+        let mostly_personalized = blend(0.2);
+        assert!(position(&mostly_personalized, popular.clone()).is_some());
+        assert!(
+            position(&mostly_personalized, niche.clone())
+                < position(&mostly_personalized, popular.clone())
+        );
+
+        let mostly_popular = blend(0.8);
+        assert!(
+            position(&mostly_popular, niche.clone()) > position(&mostly_popular, popular.clone())
+        );
+
+        let popularity = blend(1.0);
+        assert_eq!(popularity[0], popular);
+        assert!(position(&popularity, niche.clone()) > position(&popularity, popular.clone()));
+        assert_eq!(blend(f64::INFINITY), popularity);
+        assert_eq!(popularity.len(), recommender.graph.nodes().count() - 1);
+    }
+
+    #[test]
+    fn tag_only_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+            recommender.tag_object(&obj, &format!("Group {}", obj % 4));
+        }
+        recommender.add_object(&100);
+
+        let tags = recommender.recommend_tags(&[0, 2], 5, 1000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!tags.is_empty());
+        assert!(tags
             .iter()
-            .cloned()
-            .collect::<HashSet<RecommenderNode<String>>>();
+            .all(|tag| tag == "0" || tag.starts_with("Group")));
+        assert!(!tags.contains(&String::from("1")));
+        assert!(!tags.contains(&String::from("Group 1")));
 
-        assert!(!recommendations.contains(&RecommenderNode::Object(obj_0)));
-        assert!(recommendations.contains(&RecommenderNode::Tag(tag_1)));
-        assert!(recommendations.contains(&RecommenderNode::Object(obj_2)));
+        let tags = recommender.recommend_tags(&[0], 5, 1000, |_, _| 0.0, |_, _| 1.0);
+        assert!(tags.is_empty());
+        assert!(recommender
+            .recommend_tags(&[100], 5, 1000, |_, _| 1.0, |_, _| 1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn list_objects_and_tags() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        assert_eq!(recommender.objects().count(), 0);
+        assert_eq!(recommender.tags().count(), 0);
+
+        for obj in 0..10 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+        }
+        recommender.add_object(&100);
+        recommender.add_tag("Empty");
+        // Tags with the same name as an object are still tags
+        recommender.add_tag("5");
+
+        let mut objects = recommender.objects().cloned().collect::<Vec<u32>>();
+        objects.sort();
+        assert_eq!(objects, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 100]);
+
+        let mut tags = recommender.tags().collect::<Vec<&str>>();
+        tags.sort();
+        assert_eq!(tags, vec!["0", "1", "2", "5", "Empty"]);
+    }
+
+    #[test]
+    fn remove_object_keeps_tags() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object_at(&obj, &format!("{}", obj % 2), obj as u64);
+            recommender.tag_object(&obj, "All");
+        }
+        recommender.tag_object(&100, "Lonely");
+        recommender.tag_object(&100, "All");
+        assert_eq!(recommender.graph.max_degree(), 11);
+
+        recommender.remove_object(&100);
+        recommender.remove_object(&4);
+        recommender.remove_object(&1000);
+        assert_eq!(recommender.graph.max_degree(), 9);
+        assert!(!recommender.graph.contains(&RecommenderNode::Object(4)));
+        assert!(!recommender.insertion_order.contains_key(&4));
+        assert!(!recommender.edge_timestamps.contains_key(&4));
+
+        let mut tags = recommender.tags().collect::<Vec<&str>>();
+        tags.sort();
+        assert_eq!(tags, vec!["0", "1", "All", "Lonely"]);
+        assert_eq!(recommender.objects().count(), 9);
+
+        for query in [0, 1, 2].iter() {
+            let recommendations = recommender.recommendations(
+                &[RecommenderNode::Object(*query)],
+                5,
+                5000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+            );
+            assert!(!recommendations.is_empty());
+            assert!(!recommendations.contains(&RecommenderNode::Object(4)));
+            assert!(!recommendations.contains(&RecommenderNode::Object(100)));
+            assert!(!recommendations.contains(&RecommenderNode::Tag(String::from("Lonely"))));
+        }
+    }
+
+    #[test]
+    fn remove_tag_keeps_objects() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object_at(&obj, "All", obj as u64);
+            recommender.tag_object_at(&obj, &format!("{}", obj % 2), obj as u64);
+        }
+        recommender.set_tag_weight("All", 2.0);
+        assert_eq!(recommender.graph.max_degree(), 10);
+
+        recommender.remove_tag("All");
+        recommender.remove_tag("Unknown");
+        assert_eq!(recommender.graph.max_degree(), 5);
+        assert!(!recommender.tag_weights.contains_key("All"));
+        assert!(recommender
+            .edge_timestamps
+            .values()
+            .all(|tags| !tags.contains_key("All") && tags.len() == 1));
+        assert_eq!(recommender.objects().count(), 10);
+
+        let mut tags = recommender.tags().collect::<Vec<&str>>();
+        tags.sort();
+        assert_eq!(tags, vec!["0", "1"]);
+
+        // The walks no longer go through the removed tag
+        let query = RecommenderNode::Object(0);
+        let counts = recommender.recommendations_map(&query, 5, 5000, |_, _| 1.0);
+        assert!(!counts.contains_key(&RecommenderNode::Tag(String::from("All"))));
+        let recommendations =
+            recommender.object_recommendations(&[0], 5, 5000, |_, _| 1.0, |_, _| 1.0);
+        assert!(!recommendations.is_empty());
+        assert!(recommendations.iter().all(|obj| obj % 2 == 0));
+    }
+
+    #[test]
+    fn try_recommendations_reports_unknown_queries() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+        }
+        recommender.add_object(&100);
+        recommender.add_tag("Empty");
+
+        assert!(recommender.has_object(&0));
+        assert!(recommender.has_object(&100));
+        assert!(!recommender.has_object(&10));
+        assert!(recommender.has_tag("0"));
+        assert!(recommender.has_tag("Empty"));
+        assert!(!recommender.has_tag("2"));
+
+        // Known queries without recommendations are not errors
+        let recommendations = recommender.try_recommendations(
+            &[
+                RecommenderNode::Object(100),
+                RecommenderNode::Tag(String::from("Empty")),
+            ],
+            3,
+            100,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(recommendations, Ok(vec![]));
+        // And they don't take steps from the other queries
+        let recommendations = recommender
+            .try_recommendations(
+                &[RecommenderNode::Object(100), RecommenderNode::Object(0)],
+                3,
+                1000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+            )
+            .unwrap();
+        assert_eq!(recommendations.len(), 5);
+        assert!(recommendations.contains(&RecommenderNode::Object(2)));
+
+        let queries = [
+            RecommenderNode::Object(20),
+            RecommenderNode::Object(0),
+            RecommenderNode::Tag(String::from("2")),
+        ];
+        let error = recommender
+            .try_recommendations(&queries, 3, 100, |_, _| 1.0, |_, _| 1.0)
+            .unwrap_err();
+        assert_eq!(
+            error.queries,
+            vec![
+                RecommenderNode::Object(20),
+                RecommenderNode::Tag(String::from("2"))
+            ]
+        );
+
+        recommender.remove_object(&0);
+        assert!(!recommender.has_object(&0));
+        assert!(recommender
+            .try_recommendations(&queries[1..2], 3, 100, |_, _| 1.0, |_, _| 1.0)
+            .is_err());
+    }
+
+    #[test]
+    fn object_edges_influence_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, "All");
+        }
+        // Bought together
+        recommender.relate_objects(&0, &7);
+        recommender.relate_objects(&100, &0);
+        assert!(recommender.has_object(&100));
+
+        let queries = [RecommenderNode::Object(0)];
+        let recommendations = |object_to_object_weight: f32| {
+            recommender.recommendations_with_object_edges(
+                &queries,
+                3,
+                5000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                |_, _| object_to_object_weight,
+            )
+        };
+        let position = |recommendations: &[RecommenderNode<u32>], obj| {
+            recommendations
+                .iter()
+                .position(|node| *node == RecommenderNode::Object(obj))
+        };
+
+        let related = recommendations(5.0);
+        assert!(position(&related, 100).is_some());
+        for obj in 1..7 {
+            assert!(position(&related, 7) < position(&related, obj));
+        }
+
+        // Without a weight, the edges between objects are ignored
+        let unrelated = recommendations(0.0);
+        assert!(position(&unrelated, 100).is_none());
+        assert_eq!(
+            unrelated.iter().collect::<HashSet<&RecommenderNode<u32>>>(),
+            recommender
+                .recommendations(&queries, 3, 5000, |_, _| 1.0, |_, _| 1.0)
+                .iter()
+                .collect::<HashSet<&RecommenderNode<u32>>>()
+        );
+        assert!(!recommender
+            .recommendations(&queries, 3, 5000, |_, _| 1.0, |_, _| 1.0)
+            .contains(&RecommenderNode::Object(100)));
     }
 }
@@ -0,0 +1,191 @@
+//! # Ordered Graph
+//!
+//! The `ordered_graph` module is a collection of utilities to handle an
+//! undirected graph structure with a canonical iteration order.
+//!
+//! Unlike [`Graph`](../graph/struct.Graph.html), the nodes and their
+//! neighbors are always iterated in ascending order, so walks with a
+//! seeded random number generator are reproducible across graph
+//! instances, processes and platforms.
+//!
+//! The end user of the library should not need to use this module
+//! directly.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::LinkedList;
+use std::fmt;
+
+use rand::Rng;
+
+use super::graph::weighted_sample;
+
+/// Data structure containing an undirected graph, backed by ordered maps.
+pub struct OrderedGraph<T> {
+    data: BTreeMap<T, BTreeSet<T>>,
+}
+
+impl<T: Ord + Clone> OrderedGraph<T> {
+    /// Creates an empty graph
+    pub fn new() -> OrderedGraph<T> {
+        OrderedGraph {
+            data: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a node to the graph.
+    pub fn add_node(&mut self, node: &T) {
+        self.data.entry(node.clone()).or_default();
+    }
+
+    /// Adds an edge to the graph. The nodes are created, if needed.
+    pub fn add_edge(&mut self, node_a: &T, node_b: &T) {
+        self.data
+            .entry(node_a.clone())
+            .or_default()
+            .insert(node_b.clone());
+        self.data
+            .entry(node_b.clone())
+            .or_default()
+            .insert(node_a.clone());
+    }
+
+    /// Checks if a node is in the graph.
+    pub fn contains(&self, node: &T) -> bool {
+        self.data.contains_key(node)
+    }
+
+    /// Lists all nodes of the graph, in ascending order.
+    pub fn nodes(&self) -> impl Iterator<Item = &T> {
+        self.data.keys()
+    }
+
+    /// Lists the successors of a node, in ascending order.
+    pub fn successors(&self, node: &T) -> BTreeSet<T> {
+        self.data.get(node).cloned().unwrap_or_default()
+    }
+
+    /// Returns the number of neighbors of a node.
+    pub fn degree(&self, node: &T) -> usize {
+        self.data.get(node).map(|succs| succs.len()).unwrap_or(0)
+    }
+
+    /// Performs a random walk on a graph, as in
+    /// [`Graph::random_walk`](../graph/struct.Graph.html#method.random_walk),
+    /// using the given random number generator.
+    ///
+    /// It returns the list of visited nodes in reverse order. For the same
+    /// edges and the same generator state, the walk is always the same,
+    /// regardless of the order in which the edges were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::ordered_graph::OrderedGraph;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut graph: OrderedGraph<u32> = OrderedGraph::new();
+    /// for i in 1..10 {
+    ///     graph.add_edge(&0, &i);
+    /// }
+    ///
+    /// let walk = graph.random_walk(&0, 10, |_, _| 1.0, &mut StdRng::seed_from_u64(42));
+    /// assert_eq!(walk.len(), 10);
+    /// assert_eq!(
+    ///     graph.random_walk(&0, 10, |_, _| 1.0, &mut StdRng::seed_from_u64(42)),
+    ///     walk
+    /// );
+    /// ```
+    pub fn random_walk(
+        &self,
+        starting_node: &T,
+        max_hops: u8,
+        weight_fun: impl Fn(&T, &T) -> f32,
+        rng: &mut impl Rng,
+    ) -> LinkedList<T> {
+        let mut visited: LinkedList<T> = LinkedList::new();
+        if !self.data.contains_key(starting_node) {
+            return visited;
+        }
+        let mut current_node = starting_node.clone();
+        for _ in 0..max_hops {
+            visited.push_front(current_node.clone());
+            let next_node = match self.data.get(&current_node) {
+                Some(succs) => {
+                    let candidates = succs.iter().collect::<Vec<&T>>();
+                    weighted_sample(rng, &candidates, |next_node| {
+                        weight_fun(&current_node, next_node)
+                    })
+                }
+                None => None,
+            };
+            match next_node {
+                Some(next_node) => current_node = next_node,
+                None => break,
+            }
+        }
+        visited
+    }
+}
+
+impl<T: Ord + Clone> Default for OrderedGraph<T> {
+    fn default() -> OrderedGraph<T> {
+        OrderedGraph::new()
+    }
+}
+
+impl<T: fmt::Debug + Ord> fmt::Debug for OrderedGraph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OrderedGraph {:?}", self.data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn reproducible_seeded_walks() {
+        let edges = (0..50u32)
+            .flat_map(|i| vec![(i, 100 + i % 7), (i, 200 + i % 5)])
+            .collect::<Vec<(u32, u32)>>();
+
+        let mut graph: OrderedGraph<u32> = OrderedGraph::new();
+        for (a, b) in edges.iter() {
+            graph.add_edge(a, b);
+        }
+        let mut reversed: OrderedGraph<u32> = OrderedGraph::new();
+        for (a, b) in edges.iter().rev() {
+            reversed.add_edge(b, a);
+        }
+
+        let walks = |graph: &OrderedGraph<u32>| {
+            let mut rng = StdRng::seed_from_u64(494);
+            (0..20)
+                .map(|_| graph.random_walk(&0, 30, |_, to| (*to % 3 + 1) as f32, &mut rng))
+                .collect::<Vec<LinkedList<u32>>>()
+        };
+
+        let first_run = walks(&graph);
+        assert!(first_run.iter().all(|walk| walk.len() == 30));
+        assert_eq!(walks(&graph), first_run);
+        assert_eq!(walks(&reversed), first_run);
+        assert_eq!(
+            graph.nodes().collect::<Vec<&u32>>(),
+            reversed.nodes().collect::<Vec<&u32>>()
+        );
+
+        assert!(graph
+            .random_walk(&1000, 30, |_, _| 1.0, &mut StdRng::seed_from_u64(494))
+            .is_empty());
+        assert_eq!(
+            graph
+                .random_walk(&0, 30, |_, _| 0.0, &mut StdRng::seed_from_u64(494))
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! # Pagination
+//!
+//! The `pagination` module is a collection of utilities to split the
+//! recommendations in pages.
+
+use std::hash::Hash;
+
+use rand::Rng;
+
+use super::Recommender;
+use super::WeightPair;
+
+/// Opaque position of a page of recommendations.
+///
+/// The cursor stores the score of the last returned recommendation (and
+/// its position among the recommendations with the same score), so the
+/// next page starts right after it, even if the graph changes slightly
+/// between requests.
+///
+/// A cursor can be converted to a token with [`to_token`], e.g. to send
+/// it to a client, and back with [`from_token`].
+///
+/// [`to_token`]: #method.to_token
+/// [`from_token`]: #method.from_token
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PageCursor {
+    page_size: usize,
+    last_score: Option<u64>,
+    ties: usize,
+}
+
+impl PageCursor {
+    /// Creates a cursor pointing to the first page, with `page_size`
+    /// recommendations per page.
+    pub fn first(page_size: usize) -> PageCursor {
+        PageCursor {
+            page_size,
+            last_score: None,
+            ties: 0,
+        }
+    }
+
+    /// Number of recommendations per page.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Encodes this cursor as an opaque token, that can be decoded with
+    /// [`from_token`](#method.from_token).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::pagination::PageCursor;
+    ///
+    /// let cursor = PageCursor::first(10);
+    /// assert_eq!(PageCursor::from_token(&cursor.to_token()), Some(cursor));
+    /// assert_eq!(PageCursor::from_token("not a cursor"), None);
+    /// ```
+    pub fn to_token(&self) -> String {
+        match self.last_score {
+            None => format!("{:x}", self.page_size),
+            Some(last_score) => format!("{:x}.{:x}.{:x}", self.page_size, last_score, self.ties),
+        }
+    }
+
+    /// Decodes a token created by [`to_token`](#method.to_token), or
+    /// returns `None` if the token is not valid.
+    pub fn from_token(token: &str) -> Option<PageCursor> {
+        let fields = token
+            .split('.')
+            .map(|field| u64::from_str_radix(field, 16).ok())
+            .collect::<Option<Vec<u64>>>()?;
+        match fields[..] {
+            [page_size] => Some(PageCursor::first(page_size as usize)),
+            [page_size, last_score, ties] => Some(PageCursor {
+                page_size: page_size as usize,
+                last_score: Some(last_score),
+                ties: ties as usize,
+            }),
+            _ => None,
+        }
+    }
+
+    fn is_after(&self, score: f64, ties: usize) -> bool {
+        match self.last_score.map(f64::from_bits) {
+            None => true,
+            Some(last_score) => score < last_score || (score == last_score && ties >= self.ties),
+        }
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Same as [`object_recommendations_seeded`], but only the page pointed
+    /// by the `cursor` is returned, along with the cursor of the next page
+    /// (if there are more recommendations).
+    ///
+    /// When the random number generator is in the same state for every
+    /// page, the pages never overlap.
+    ///
+    /// [`object_recommendations_seeded`]: struct.Recommender.html#method.object_recommendations_seeded
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::pagination::PageCursor;
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::WeightPair;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut recommender: Recommender<u32> = Recommender::new();
+    /// for obj in 0..10 {
+    ///     recommender.tag_object(&obj, "Numbers");
+    /// }
+    ///
+    /// let weights = WeightPair::uniform();
+    /// let (first_page, cursor) = recommender.object_recommendations_page(
+    ///     &[0],
+    ///     3,
+    ///     1000,
+    ///     &weights,
+    ///     &mut StdRng::seed_from_u64(42),
+    ///     &PageCursor::first(5),
+    /// );
+    /// let (second_page, cursor) = recommender.object_recommendations_page(
+    ///     &[0],
+    ///     3,
+    ///     1000,
+    ///     &weights,
+    ///     &mut StdRng::seed_from_u64(42),
+    ///     &cursor.unwrap(),
+    /// );
+    ///
+    /// assert_eq!(first_page.len(), 5);
+    /// assert_eq!(second_page.len(), 4);
+    /// assert!(cursor.is_none());
+    /// ```
+    pub fn object_recommendations_page(
+        &self,
+        queries: &[T],
+        depth: u8,
+        max_total_steps: usize,
+        weights: &WeightPair<T>,
+        rng: &mut impl Rng,
+        cursor: &PageCursor,
+    ) -> (Vec<T>, Option<PageCursor>) {
+        let scored =
+            self.seeded_object_scores(queries, depth, max_total_steps, rng, None, |from, to| {
+                self.walk_weight(from, to, &weights.object_to_tag, &weights.tag_to_object)
+            });
+
+        let mut page: Vec<T> = Vec::new();
+        let mut next_cursor = *cursor;
+        let mut has_more = false;
+        let mut previous_score: Option<f64> = None;
+        let mut ties = 0;
+        for (obj, score) in scored {
+            if previous_score == Some(score) {
+                ties += 1;
+            } else {
+                previous_score = Some(score);
+                ties = 0;
+            }
+            if !cursor.is_after(score, ties) {
+                continue;
+            }
+            if page.len() == cursor.page_size {
+                has_more = true;
+                break;
+            }
+            page.push(obj);
+            next_cursor.last_score = Some(score.to_bits());
+            next_cursor.ties = ties + 1;
+        }
+
+        (page, if has_more { Some(next_cursor) } else { None })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn paginate_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..40 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+            recommender.tag_object(&obj, &format!("{}", obj % 5 + 10));
+        }
+        let weights = WeightPair::uniform();
+        let rng = StdRng::seed_from_u64(7);
+
+        let all = recommender.object_recommendations_seeded(
+            &[0],
+            5,
+            2000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut rng.clone(),
+        );
+        assert!(all.len() > 20);
+
+        let (first_page, cursor) = recommender.object_recommendations_page(
+            &[0],
+            5,
+            2000,
+            &weights,
+            &mut rng.clone(),
+            &PageCursor::first(10),
+        );
+        let cursor = cursor.unwrap();
+        let token = cursor.to_token();
+        assert_eq!(PageCursor::from_token(&token), Some(cursor));
+        let (second_page, _) = recommender.object_recommendations_page(
+            &[0],
+            5,
+            2000,
+            &weights,
+            &mut rng.clone(),
+            &PageCursor::from_token(&token).unwrap(),
+        );
+
+        assert_eq!(first_page.len(), 10);
+        assert_eq!(second_page.len(), 10);
+        let first_set: HashSet<&u32> = first_page.iter().collect();
+        assert!(second_page.iter().all(|obj| !first_set.contains(obj)));
+        assert_eq!(&all[..10], &first_page[..]);
+        assert_eq!(&all[10..20], &second_page[..]);
+
+        assert_eq!(PageCursor::from_token(""), None);
+        assert_eq!(PageCursor::from_token("a.b"), None);
+        assert_eq!(PageCursor::from_token("a.b.c.d"), None);
+    }
+
+    #[test]
+    fn paginate_fractional_scores() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..40 {
+            recommender.tag_object(&obj, &format!("{}", obj % 3));
+            recommender.tag_object(&obj, &format!("{}", obj % 5 + 10));
+        }
+        let weights = WeightPair::uniform();
+        let rng = StdRng::seed_from_u64(11);
+
+        // Multiple queries produce fractional scores
+        let all = recommender.object_recommendations_seeded(
+            &[0, 1, 2],
+            5,
+            3000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+            &mut rng.clone(),
+        );
+
+        let mut pages: Vec<u32> = Vec::new();
+        let mut cursor = PageCursor::first(3);
+        loop {
+            let (page, next_cursor) = recommender.object_recommendations_page(
+                &[0, 1, 2],
+                5,
+                3000,
+                &weights,
+                &mut rng.clone(),
+                &cursor,
+            );
+            pages.extend(page);
+            match next_cursor {
+                Some(next_cursor) => {
+                    cursor = PageCursor::from_token(&next_cursor.to_token()).unwrap()
+                }
+                None => break,
+            }
+        }
+        assert_eq!(pages, all);
+    }
+}
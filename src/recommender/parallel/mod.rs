@@ -0,0 +1,170 @@
+//! # Parallel
+//!
+//! The `parallel` module is a collection of utilities to run the walks of
+//! a query on multiple threads.
+//!
+//! This module is only available with the `rayon` feature.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rayon::prelude::*;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Number of steps of each batch of walks that runs on a single thread.
+const BATCH_STEPS: usize = 4096;
+
+impl<T: Eq + Clone + Hash + Send + Sync> Recommender<T> {
+    /// Same as [`recommendations`], but the walks of each query are split
+    /// into batches that run in parallel, on rayon's thread pool.
+    ///
+    /// Each batch has its own random number generator, seeded from `seed`
+    /// and the position of the batch, so for the same recommender,
+    /// arguments and `seed`, the results are always the same, regardless
+    /// of the number of threads.
+    ///
+    /// The batches only share the graph, so for large step budgets (e.g.
+    /// hundreds of thousands of steps) the speedup is close to the number
+    /// of cores. For small budgets, there are few batches and the threads
+    /// overhead can make this slower than [`recommendations`].
+    ///
+    /// [`recommendations`]: struct.Recommender.html#method.recommendations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let recommendations = recommender.recommendations_parallel(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100000,
+    ///     42,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_parallel(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        seed: u64,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32 + Sync,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32 + Sync,
+    ) -> Vec<RecommenderNode<T>> {
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |i, q, steps| {
+                // Each query gets a different range of batch seeds
+                let query_seed = seed.wrapping_add((i as u64) << 32);
+                self.batched_recommendations_map(q, depth, steps, query_seed, true, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+            });
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Counts the visits of the walks from a node, split into batches of
+    /// [`BATCH_STEPS`] steps, that run in parallel if `parallel` is set.
+    ///
+    /// The batch counts are merged in order, so the result does not
+    /// depend on which batches run in parallel.
+    fn batched_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        seed: u64,
+        parallel: bool,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32 + Sync,
+    ) -> HashMap<RecommenderNode<T>, u32> {
+        let batches = max_total_steps.div_ceil(BATCH_STEPS);
+        let batch_counts = |batch: usize| {
+            let steps = BATCH_STEPS.min(max_total_steps - batch * BATCH_STEPS);
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(batch as u64));
+            let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+            Recommender::accumulate_walks(
+                steps,
+                || {
+                    self.graph
+                        .random_walk_with_rng(from, depth, &weight_fun, &mut rng)
+                },
+                |visits| {
+                    for visited in visits {
+                        *acc.entry(visited).or_insert(0) += 1;
+                    }
+                },
+            );
+            acc
+        };
+        let all_counts: Vec<HashMap<RecommenderNode<T>, u32>> = if parallel {
+            (0..batches).into_par_iter().map(batch_counts).collect()
+        } else {
+            (0..batches).map(batch_counts).collect()
+        };
+
+        let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+        for counts in all_counts {
+            for (node, count) in counts {
+                *acc.entry(node).or_insert(0) += count;
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..200 {
+            recommender.tag_object(&obj, &format!("{}", obj % 10));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+        let query = RecommenderNode::Object(0);
+        let counts = |seed: u64, parallel: bool| {
+            recommender.batched_recommendations_map(&query, 5, 50000, seed, parallel, |_, _| 1.0)
+        };
+
+        let sequential = counts(515, false);
+        let parallel = counts(515, true);
+        assert_eq!(parallel, sequential);
+        assert_eq!(counts(515, true), parallel);
+        assert_ne!(counts(516, true), parallel);
+        let steps: u32 = parallel.values().sum();
+        assert!((50000..50000 + 5 * 13).contains(&steps));
+
+        // The same nodes as the sequential walks are visited
+        let visited = |counts: &HashMap<RecommenderNode<u32>, u32>| {
+            let mut nodes = counts
+                .keys()
+                .cloned()
+                .collect::<Vec<RecommenderNode<u32>>>();
+            nodes.sort_by_key(|node| format!("{:?}", node));
+            nodes
+        };
+        let walks = recommender.recommendations_map(&query, 5, 50000, |_, _| 1.0);
+        assert_eq!(visited(&parallel), visited(&walks));
+
+        assert!(recommender
+            .batched_recommendations_map(&query, 5, 0, 515, true, |_, _| 1.0)
+            .is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! # Serialization
+//!
+//! The `serialization` module is a collection of utilities to store a
+//! recommender and load it back, e.g. to build it offline and load it
+//! when a service starts.
+//!
+//! This module is only available with the `serde` feature. The binary
+//! persistence to files also requires the `bincode` feature.
+
+use std::collections::HashMap;
+#[cfg(feature = "bincode")]
+use std::fs::File;
+use std::hash::Hash;
+#[cfg(feature = "bincode")]
+use std::io;
+#[cfg(feature = "bincode")]
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use super::graph::Graph;
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Serialized form of a recommender. The maps indexed by objects are
+/// stored as lists, as the objects might not be valid map keys in some
+/// formats (e.g. JSON).
+#[derive(Deserialize)]
+struct SerializedRecommender<T: Eq + Clone + Hash> {
+    graph: Graph<RecommenderNode<T>>,
+    edge_timestamps: Vec<(T, HashMap<String, u64>)>,
+    insertion_order: Vec<(T, u64)>,
+    next_insertion: u64,
+    tag_weights: HashMap<String, f32>,
+}
+
+/// The blocklist is shared with other handles, so it is not serialized.
+impl<T: Serialize + Eq + Clone + Hash> Serialize for Recommender<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The graph is serialized directly, to avoid cloning it
+        #[derive(Serialize)]
+        struct SerializedRecommenderRef<'a, T: Eq + Clone + Hash> {
+            graph: &'a Graph<RecommenderNode<T>>,
+            edge_timestamps: Vec<(&'a T, &'a HashMap<String, u64>)>,
+            insertion_order: Vec<(&'a T, u64)>,
+            next_insertion: u64,
+            tag_weights: &'a HashMap<String, f32>,
+        }
+
+        SerializedRecommenderRef {
+            graph: &self.graph,
+            edge_timestamps: self.edge_timestamps.iter().collect(),
+            insertion_order: self
+                .insertion_order
+                .iter()
+                .map(|(obj, order)| (obj, *order))
+                .collect(),
+            next_insertion: self.next_insertion,
+            tag_weights: &self.tag_weights,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The deserialized recommender has no blocklist.
+impl<'de, T: DeserializeOwned + Eq + Clone + Hash> Deserialize<'de> for Recommender<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Recommender<T>, D::Error> {
+        let serialized = SerializedRecommender::<T>::deserialize(deserializer)?;
+        Ok(Recommender {
+            graph: serialized.graph,
+            edge_timestamps: serialized.edge_timestamps.into_iter().collect(),
+            insertion_order: serialized.insertion_order.into_iter().collect(),
+            next_insertion: serialized.next_insertion,
+            blocklist: None,
+            tag_weights: serialized.tag_weights,
+        })
+    }
+}
+
+#[cfg(feature = "bincode")]
+fn bincode_to_io_error(error: bincode::ErrorKind) -> io::Error {
+    match error {
+        bincode::ErrorKind::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<T: Serialize + DeserializeOwned + Eq + Clone + Hash> Recommender<T> {
+    /// Stores the recommender in a binary file, which is created or
+    /// truncated, so that it can be loaded with
+    /// [`load_from_path`](#method.load_from_path).
+    ///
+    /// The blocklist is not stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    ///
+    /// let path = std::env::temp_dir().join("pixie-rust-save-example.bin");
+    /// recommender.save_to_path(&path).unwrap();
+    ///
+    /// let restored: Recommender<String> = Recommender::load_from_path(&path).unwrap();
+    /// assert_eq!(
+    ///     restored.object_recommendations(
+    ///         &[String::from("The Raid")],
+    ///         3,
+    ///         100,
+    ///         |_, _| 1.0,
+    ///         |_, _| 1.0
+    ///     ),
+    ///     vec![String::from("Rocky")]
+    /// );
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn save_to_path(&self, path: &Path) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(File::create(path)?);
+        bincode::serialize_into(&mut writer, self).map_err(|error| bincode_to_io_error(*error))?;
+        io::Write::flush(&mut writer)
+    }
+
+    /// Loads a recommender stored with
+    /// [`save_to_path`](#method.save_to_path).
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the file is not a
+    /// valid recommender.
+    pub fn load_from_path(path: &Path) -> io::Result<Recommender<T>> {
+        let reader = io::BufReader::new(File::open(path)?);
+        bincode::deserialize_from(reader).map_err(|error| bincode_to_io_error(*error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted<T: Ord + Clone>(elems: &[T]) -> Vec<T> {
+        let mut elems = elems.to_vec();
+        elems.sort();
+        elems
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..50 {
+            recommender.tag_object(&obj, &format!("{}", obj % 5));
+            recommender.tag_object(&obj, &format!("Group {}", obj % 3));
+        }
+        recommender.add_object(&100);
+        recommender.add_tag("Empty");
+        recommender.set_tag_weight("0", 2.0);
+
+        let json = serde_json::to_string(&recommender).unwrap();
+        let restored: Recommender<u32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.graph.max_degree(), recommender.graph.max_degree());
+        assert_eq!(restored.edge_timestamps, recommender.edge_timestamps);
+        assert_eq!(restored.insertion_order, recommender.insertion_order);
+        assert_eq!(restored.next_insertion, recommender.next_insertion);
+        assert_eq!(restored.tag_weights, recommender.tag_weights);
+        assert_eq!(
+            restored.structural_fingerprint(),
+            recommender.structural_fingerprint()
+        );
+        for node in recommender.graph.nodes() {
+            assert_eq!(
+                restored.graph.successors(node),
+                recommender.graph.successors(node)
+            );
+        }
+
+        let recommendations = |recommender: &Recommender<u32>| {
+            sorted(&recommender.object_recommendations(&[0], 5, 10000, |_, _| 1.0, |_, _| 1.0))
+        };
+        assert_eq!(recommendations(&restored), recommendations(&recommender));
+        let topk = |recommender: &Recommender<u32>| {
+            recommender.object_recommendations_topk_exact(&[0], 3, 0.3, |_, _| 1.0, |_, _| 1.0)
+        };
+        assert_eq!(topk(&recommender), vec![15, 30, 45]);
+        assert_eq!(topk(&restored), topk(&recommender));
+    }
+
+    #[test]
+    fn json_round_trip_weighted_graph() {
+        let mut graph: Graph<RecommenderNode<String>> = Graph::new();
+        let tag = RecommenderNode::Tag(String::from("Action"));
+        let raid = RecommenderNode::Object(String::from("The Raid"));
+        let rocky = RecommenderNode::Object(String::from("Rocky"));
+        graph.add_weighted_edge(&tag, &raid, 5.0);
+        graph.add_edge(&tag, &rocky);
+        graph.add_edge(&raid, &raid);
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let restored: Graph<RecommenderNode<String>> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.edge_weight(&raid, &tag), Some(5.0));
+        assert_eq!(restored.edge_weight(&rocky, &tag), Some(1.0));
+        assert_eq!(restored.edge_weight(&raid, &raid), Some(1.0));
+        assert_eq!(restored.degree(&raid), 2);
+        assert_eq!(restored.max_degree(), 2);
+        assert_eq!(restored.average_degree(), graph.average_degree());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_file_round_trip() {
+        let mut recommender: Recommender<String> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&format!("Object {}", obj), &format!("Tag {}", obj % 4));
+        }
+        recommender.add_tag("Empty");
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        recommender.save_to_path(file.path()).unwrap();
+        let restored: Recommender<String> = Recommender::load_from_path(file.path()).unwrap();
+        assert_eq!(
+            restored.structural_fingerprint(),
+            recommender.structural_fingerprint()
+        );
+        assert_eq!(restored.insertion_order, recommender.insertion_order);
+        let recommendations = |recommender: &Recommender<String>| {
+            sorted(&recommender.object_recommendations(
+                &[String::from("Object 0")],
+                3,
+                10000,
+                |_, _| 1.0,
+                |_, _| 1.0,
+            ))
+        };
+        assert_eq!(recommendations(&restored), recommendations(&recommender));
+        assert_eq!(recommendations(&restored).len(), 4);
+
+        // Corrupted and missing files are errors
+        std::fs::write(file.path(), [1, 2, 3]).unwrap();
+        let error = Recommender::<String>::load_from_path(file.path()).unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+        ));
+        let missing = file.path().with_extension("missing");
+        let error = Recommender::<String>::load_from_path(&missing).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        let error = recommender
+            .save_to_path(&missing.join("recommender.bin"))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,126 @@
+//! # Shared
+//!
+//! The `shared` module is a collection of utilities to share a
+//! recommender between threads, while allowing it to be replaced.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use super::Recommender;
+
+/// A recommender that can be atomically replaced while it's being used.
+///
+/// Readers call [`load`] to get a snapshot of the current recommender,
+/// which remains valid even if a new recommender is stored in the
+/// meantime. This allows a recommender to be rebuilt offline and swapped
+/// in without interrupting in-flight requests.
+///
+/// # Examples
+///
+/// ```
+/// use pixie_rust::recommender::Recommender;
+/// use pixie_rust::recommender::shared::SharedRecommender;
+///
+/// let shared: SharedRecommender<String> = SharedRecommender::new(Recommender::new());
+///
+/// let old_snapshot = shared.load();
+///
+/// let mut new_recommender: Recommender<String> = Recommender::new();
+/// new_recommender.tag_object(&String::from("The Raid"), "Action");
+/// shared.store(new_recommender);
+///
+/// assert_eq!(old_snapshot.validate().components, 0);
+/// assert_eq!(shared.load().validate().components, 1);
+/// ```
+///
+/// [`load`]: #method.load
+pub struct SharedRecommender<T> {
+    current: RwLock<Arc<Recommender<T>>>,
+}
+
+impl<T> SharedRecommender<T> {
+    /// Creates a new shared recommender.
+    pub fn new(recommender: Recommender<T>) -> SharedRecommender<T> {
+        SharedRecommender {
+            current: RwLock::new(Arc::new(recommender)),
+        }
+    }
+
+    /// Returns a snapshot of the current recommender.
+    pub fn load(&self) -> Arc<Recommender<T>> {
+        // The lock only guards the pointer swap, so a poisoned lock
+        // can't leave the recommender in an inconsistent state.
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replaces the current recommender, returning the previous one.
+    ///
+    /// Snapshots that were previously loaded keep pointing to the previous
+    /// recommender.
+    pub fn store(&self, recommender: Recommender<T>) -> Arc<Recommender<T>> {
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut *current, Arc::new(recommender))
+    }
+}
+
+impl<T: fmt::Debug + Eq + std::hash::Hash> fmt::Debug for SharedRecommender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SharedRecommender [{:?}]", self.load())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn snapshots_survive_swaps() {
+        let raid = String::from("The Raid");
+        let rocky = String::from("Rocky");
+        let python = String::from("Monty Python and The Holy Grail");
+
+        let mut old_recommender: Recommender<String> = Recommender::new();
+        old_recommender.tag_object(&raid, "Action");
+        old_recommender.tag_object(&rocky, "Action");
+
+        let mut new_recommender: Recommender<String> = Recommender::new();
+        new_recommender.tag_object(&raid, "Comedy");
+        new_recommender.tag_object(&python, "Comedy");
+
+        let shared = Arc::new(SharedRecommender::new(old_recommender));
+        let snapshot = shared.load();
+
+        let updater = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                shared.store(new_recommender);
+            })
+        };
+        updater.join().unwrap();
+
+        let old_recommendations = snapshot.object_recommendations(
+            std::slice::from_ref(&raid),
+            3,
+            100,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert_eq!(old_recommendations, vec![rocky]);
+
+        let new_recommendations =
+            shared
+                .load()
+                .object_recommendations(&[raid], 3, 100, |_, _| 1.0, |_, _| 1.0);
+        assert_eq!(new_recommendations, vec![python]);
+
+        assert!(!Arc::ptr_eq(&snapshot, &shared.load()));
+    }
+}
@@ -0,0 +1,285 @@
+//! # Similarity Index
+//!
+//! The `similarity_index` module is a collection of utilities to
+//! precompute the most similar objects of each object, so that they can
+//! be served without running any walk.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::Recommender;
+use super::RecommenderNode;
+use super::WeightPair;
+
+/// Precomputed similar objects of each object, along with their scores.
+pub struct SimilarityIndex<T> {
+    similarities: HashMap<T, Vec<(T, f64)>>,
+}
+
+impl<T: Eq + Hash> SimilarityIndex<T> {
+    /// Returns the precomputed similar objects of an object (best first),
+    /// or `None` if the object was not precomputed.
+    pub fn similar(&self, object: &T) -> Option<&[(T, f64)]> {
+        self.similarities
+            .get(object)
+            .map(|similar| similar.as_slice())
+    }
+
+    /// Returns the number of precomputed objects.
+    pub fn len(&self) -> usize {
+        self.similarities.len()
+    }
+
+    /// Checks if no object was precomputed.
+    pub fn is_empty(&self) -> bool {
+        self.similarities.is_empty()
+    }
+}
+
+impl<T: fmt::Debug + Eq + Hash> fmt::Debug for SimilarityIndex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SimilarityIndex {:?}", self.similarities)
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Precomputes the best `limit` similar objects of every object of
+    /// this recommender.
+    ///
+    /// The objects in the blocklist when this is called are excluded, but
+    /// later changes to the blocklist don't update the index.
+    ///
+    /// See [`precompute_similarities_of`](#method.precompute_similarities_of).
+    pub fn precompute_similarities(
+        &self,
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        weights: &WeightPair<T>,
+        seed: u64,
+    ) -> SimilarityIndex<T> {
+        let objects = self
+            .graph
+            .nodes()
+            .flat_map(|node| match node {
+                RecommenderNode::Object(obj) => Some(obj.clone()),
+                RecommenderNode::Tag(_) => None,
+            })
+            .collect::<Vec<T>>();
+        self.precompute_similarities_of(&objects, limit, depth, max_total_steps, weights, seed)
+    }
+
+    /// Precomputes the best `limit` similar objects of each of the given
+    /// `objects` (e.g. the most popular ones).
+    ///
+    /// The similar objects of each object are the same as the ones that
+    /// [`object_recommendations_scored_seeded`] returns with a generator
+    /// created from `seed`, so the index can be rebuilt reproducibly.
+    ///
+    /// [`object_recommendations_scored_seeded`]: #method.object_recommendations_scored_seeded
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::WeightPair;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// let index = recommender.precompute_similarities_of(
+    ///     &[raid.clone()],
+    ///     10,
+    ///     3,
+    ///     100,
+    ///     &WeightPair::uniform(),
+    ///     42
+    /// );
+    ///
+    /// assert_eq!(index.similar(&raid).unwrap()[0].0, rocky);
+    /// assert!(index.similar(&python).is_none());
+    /// ```
+    pub fn precompute_similarities_of(
+        &self,
+        objects: &[T],
+        limit: usize,
+        depth: u8,
+        max_total_steps: usize,
+        weights: &WeightPair<T>,
+        seed: u64,
+    ) -> SimilarityIndex<T> {
+        let similarities = objects
+            .iter()
+            .map(|obj| {
+                let mut similar = self.object_recommendations_scored_seeded(
+                    std::slice::from_ref(obj),
+                    depth,
+                    max_total_steps,
+                    &weights.object_to_tag,
+                    &weights.tag_to_object,
+                    &mut StdRng::seed_from_u64(seed),
+                    None,
+                );
+                similar.truncate(limit);
+                (obj.clone(), similar)
+            })
+            .collect();
+        SimilarityIndex { similarities }
+    }
+
+    /// Returns the best `top_k` objects that share tags with `object`,
+    /// scored by the Jaccard index of their tags (the number of shared
+    /// tags over the number of tags of either object), without running
+    /// any walk.
+    ///
+    /// Objects with the same score are ordered by the time they were
+    /// added to the recommender, so the result is always the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    /// let creed = String::from("Creed");
+    /// let python = String::from("Monty Python and The Holy Grail");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    /// recommender.tag_object(&rocky, "Drama");
+    /// recommender.tag_object(&creed, "Action");
+    /// recommender.tag_object(&creed, "Drama");
+    /// recommender.tag_object(&python, "Comedy");
+    ///
+    /// assert_eq!(
+    ///     recommender.similar_objects(&rocky, 10),
+    ///     vec![(creed, 1.0), (raid, 0.5)]
+    /// );
+    /// ```
+    pub fn similar_objects(&self, object: &T, top_k: usize) -> Vec<(T, f32)> {
+        let object_node = RecommenderNode::Object(object.clone());
+        let tags = self
+            .graph
+            .successors(&object_node)
+            .into_iter()
+            .filter(|node| matches!(node, RecommenderNode::Tag(_)))
+            .collect::<HashSet<RecommenderNode<T>>>();
+
+        let is_blocked = self.blocked_objects();
+        let mut shared_tags: HashMap<T, usize> = HashMap::new();
+        for tag in tags.iter() {
+            for node in self.graph.successors(tag) {
+                match node {
+                    RecommenderNode::Object(obj) if obj != *object && !is_blocked(&obj) => {
+                        *shared_tags.entry(obj).or_insert(0) += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut similar = shared_tags
+            .into_iter()
+            .map(|(obj, shared)| {
+                let obj_tags = self
+                    .graph
+                    .successors(&RecommenderNode::Object(obj.clone()))
+                    .iter()
+                    .filter(|node| matches!(node, RecommenderNode::Tag(_)))
+                    .count();
+                let score = shared as f32 / (tags.len() + obj_tags - shared) as f32;
+                (obj, score)
+            })
+            .collect::<Vec<(T, f32)>>();
+        similar.sort_by(|(obj_a, a), (obj_b, b)| {
+            b.partial_cmp(a)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.insertion_rank(obj_a).cmp(&self.insertion_rank(obj_b)))
+        });
+        similar.truncate(top_k);
+        similar
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup_matches_fresh_recommendations() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..30 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+            recommender.tag_object(&obj, &format!("{}", obj % 5 + 10));
+        }
+        let weights = WeightPair::uniform();
+
+        let index = recommender.precompute_similarities(5, 5, 500, &weights, 488);
+        assert_eq!(index.len(), 30);
+        assert!(index.similar(&100).is_none());
+
+        for obj in [0, 7, 29].iter() {
+            let fresh = recommender.object_recommendations_scored_seeded(
+                &[*obj],
+                5,
+                500,
+                |_, _| 1.0,
+                |_, _| 1.0,
+                &mut StdRng::seed_from_u64(488),
+                None,
+            );
+            assert_eq!(index.similar(obj).unwrap(), &fresh[..5]);
+        }
+
+        let hot = recommender.precompute_similarities_of(&[1, 2], 5, 5, 500, &weights, 488);
+        assert_eq!(hot.len(), 2);
+        assert_eq!(hot.similar(&1), index.similar(&1));
+    }
+
+    #[test]
+    fn jaccard_similar_objects() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        // Objects 0 to 3 share the "Even" tag, and 0 and 2 also share "Half"
+        for obj in 0..4 {
+            recommender.tag_object(&obj, "Even");
+        }
+        recommender.tag_object(&0, "Half");
+        recommender.tag_object(&2, "Half");
+        recommender.tag_object(&3, "Other");
+        recommender.tag_object(&10, "Other");
+        recommender.add_object(&20);
+
+        let similar = recommender.similar_objects(&0, 10);
+        assert_eq!(similar, vec![(2, 1.0), (1, 0.5), (3, 1.0 / 3.0)]);
+        for _ in 0..10 {
+            assert_eq!(recommender.similar_objects(&0, 10), similar);
+        }
+        assert_eq!(recommender.similar_objects(&0, 2), similar[..2].to_vec());
+        assert!(recommender.similar_objects(&0, 0).is_empty());
+
+        // Ties follow the insertion order
+        assert_eq!(
+            recommender.similar_objects(&1, 10),
+            vec![(0, 0.5), (2, 0.5), (3, 0.5)]
+        );
+        assert_eq!(recommender.similar_objects(&10, 10), vec![(3, 0.5)]);
+        assert!(recommender.similar_objects(&20, 10).is_empty());
+        assert!(recommender.similar_objects(&30, 10).is_empty());
+    }
+}
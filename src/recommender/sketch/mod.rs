@@ -0,0 +1,166 @@
+//! # Sketch
+//!
+//! The `sketch` module is a collection of utilities to approximately
+//! count node visits in bounded memory.
+//!
+//! The end user of the library should only need the [`SketchConfig`]
+//! from this module.
+//!
+//! [`SketchConfig`]: struct.SketchConfig.html
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+/// Configuration of an approximate visit counter.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SketchConfig {
+    width: usize,
+    depth: usize,
+    top_k: usize,
+}
+
+impl SketchConfig {
+    /// Creates a new configuration.
+    ///
+    /// The `width` and `depth` are the dimensions of the underlying
+    /// Count-Min Sketch (larger values give better estimates),
+    /// while `top_k` is the number of best candidates that are tracked.
+    pub fn new(width: usize, depth: usize, top_k: usize) -> SketchConfig {
+        SketchConfig {
+            width: width.max(1),
+            depth: depth.max(1),
+            top_k,
+        }
+    }
+}
+
+/// Count-Min Sketch that estimates how many times each item was seen.
+///
+/// The estimates are never lower than the real count.
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    counters: Vec<u32>,
+}
+
+impl CountMinSketch {
+    /// Creates an empty sketch with `depth` rows of `width` counters.
+    pub fn new(width: usize, depth: usize) -> CountMinSketch {
+        let width = width.max(1);
+        let depth = depth.max(1);
+        CountMinSketch {
+            width,
+            depth,
+            counters: vec![0; width * depth],
+        }
+    }
+
+    fn index<K: Hash>(&self, row: usize, item: &K) -> usize {
+        let mut hasher = DefaultHasher::new();
+        row.hash(&mut hasher);
+        item.hash(&mut hasher);
+        row * self.width + (hasher.finish() as usize) % self.width
+    }
+
+    /// Counts one more occurrence of an item, returning its new estimate.
+    pub fn increment<K: Hash>(&mut self, item: &K) -> u32 {
+        let mut estimate = u32::MAX;
+        for row in 0..self.depth {
+            let idx = self.index(row, item);
+            self.counters[idx] = self.counters[idx].saturating_add(1);
+            estimate = estimate.min(self.counters[idx]);
+        }
+        estimate
+    }
+
+    /// Returns the estimated number of occurrences of an item.
+    pub fn estimate<K: Hash>(&self, item: &K) -> u32 {
+        (0..self.depth)
+            .map(|row| self.counters[self.index(row, item)])
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// Approximate counter that only keeps the items with the highest counts.
+pub struct TopKCounter<K> {
+    sketch: CountMinSketch,
+    top_k: usize,
+    candidates: HashMap<K, u32>,
+}
+
+impl<K: Eq + Clone + Hash> TopKCounter<K> {
+    /// Creates an empty counter.
+    pub fn new(config: &SketchConfig) -> TopKCounter<K> {
+        TopKCounter {
+            sketch: CountMinSketch::new(config.width, config.depth),
+            top_k: config.top_k,
+            candidates: HashMap::new(),
+        }
+    }
+
+    /// Counts one more occurrence of an item.
+    pub fn increment(&mut self, item: K) {
+        let estimate = self.sketch.increment(&item);
+        if let Some(count) = self.candidates.get_mut(&item) {
+            *count = estimate;
+        } else if self.candidates.len() < self.top_k {
+            self.candidates.insert(item, estimate);
+        } else {
+            let weakest = self
+                .candidates
+                .iter()
+                .min_by_key(|(_, count)| **count)
+                .map(|(k, count)| (k.clone(), *count));
+            if let Some((weakest_key, weakest_count)) = weakest {
+                if estimate > weakest_count {
+                    self.candidates.remove(&weakest_key);
+                    self.candidates.insert(item, estimate);
+                }
+            }
+        }
+    }
+
+    /// Returns the estimated counts of the tracked items.
+    pub fn into_counts(self) -> HashMap<K, u32> {
+        self.candidates
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sketch_never_underestimates() {
+        let mut sketch = CountMinSketch::new(16, 4);
+        for i in 0..100u32 {
+            for _ in 0..i {
+                sketch.increment(&i);
+            }
+        }
+        for i in 0..100u32 {
+            assert!(sketch.estimate(&i) >= i);
+        }
+        assert_eq!(CountMinSketch::new(16, 4).estimate(&1), 0);
+    }
+
+    #[test]
+    fn top_k_counter_keeps_heavy_hitters() {
+        let mut counter: TopKCounter<u32> = TopKCounter::new(&SketchConfig::new(64, 4, 5));
+        for round in 0..50u32 {
+            for i in 0..1000u32 {
+                if i < 5 || round == 0 {
+                    counter.increment(i);
+                }
+            }
+        }
+        let counts = counter.into_counts();
+        assert_eq!(counts.len(), 5);
+        for i in 0..5 {
+            assert!(counts.get(&i).unwrap() >= &50);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! # Stream
+//!
+//! The `stream` module is a collection of utilities to compute
+//! recommendations for queries that arrive continuously.
+//!
+//! This module is only available with the `stream` feature.
+
+use std::hash::Hash;
+use std::sync::Arc;
+
+use futures::stream::Stream;
+use futures::stream::StreamExt;
+
+use super::Recommender;
+
+impl<T: Eq + Clone + Hash + Send + Sync + 'static> Recommender<T> {
+    /// Receives a stream of queries (that can only be objects) and
+    /// returns a stream with the
+    /// [`object_recommendations`](#method.object_recommendations) of each
+    /// one, in the same order.
+    ///
+    /// The random walks of each query are executed on tokio's blocking
+    /// thread pool, to avoid blocking the executor, so the stream must be
+    /// polled from a tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::stream;
+    /// use futures::StreamExt;
+    /// use pixie_rust::recommender::Recommender;
+    /// use std::sync::Arc;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let queries = stream::iter(vec![vec![raid.clone()], vec![rocky.clone()]]);
+    /// let recommendations = Arc::new(recommender).object_recommendations_stream(
+    ///     queries,
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread()
+    ///     .build()
+    ///     .unwrap();
+    /// let recommendations = runtime.block_on(recommendations.collect::<Vec<Vec<String>>>());
+    /// assert_eq!(recommendations, vec![vec![rocky], vec![raid]]);
+    /// ```
+    pub fn object_recommendations_stream(
+        self: Arc<Self>,
+        queries: impl Stream<Item = Vec<T>>,
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32 + Send + Sync + 'static,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32 + Send + Sync + 'static,
+    ) -> impl Stream<Item = Vec<T>> {
+        let weights = Arc::new((object_to_tag_weight, tag_to_object_weight));
+        queries.then(move |query| {
+            let recommender = self.clone();
+            let weights = weights.clone();
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let (object_to_tag_weight, tag_to_object_weight) = &*weights;
+                    recommender.object_recommendations(
+                        &query,
+                        depth,
+                        max_total_steps,
+                        object_to_tag_weight,
+                        tag_to_object_weight,
+                    )
+                })
+                .await
+                .expect("Failed to compute the recommendations")
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::stream;
+
+    #[test]
+    fn one_output_per_query() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..10 {
+            recommender.tag_object(&obj, &format!("{}", obj % 2));
+        }
+        recommender.add_object(&100);
+
+        let queries = vec![vec![0], vec![1], vec![100], vec![], vec![2, 3]];
+        let recommendations = Arc::new(recommender).object_recommendations_stream(
+            stream::iter(queries.clone()),
+            3,
+            1000,
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let recommendations = runtime.block_on(recommendations.collect::<Vec<Vec<u32>>>());
+        assert_eq!(recommendations.len(), queries.len());
+        for (query, recommendations) in queries.iter().zip(recommendations.iter()) {
+            assert!(recommendations.iter().all(|obj| !query.contains(obj)));
+        }
+        assert_eq!(recommendations[0].len(), 4);
+        assert!(recommendations[0].iter().all(|obj| obj % 2 == 0));
+        assert!(recommendations[1].iter().all(|obj| obj % 2 == 1));
+        assert!(recommendations[2].is_empty());
+        assert!(recommendations[3].is_empty());
+        assert_eq!(recommendations[4].len(), 8);
+    }
+}
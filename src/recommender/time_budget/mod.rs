@@ -0,0 +1,169 @@
+//! # Time Budget
+//!
+//! The `time_budget` module is a collection of utilities to bound the
+//! wall-clock time spent on the walks, in addition to the number of
+//! steps, for predictable latencies.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Same as [`recommendations`], but the walks also stop once
+    /// `max_duration` has elapsed, whichever limit comes first.
+    ///
+    /// The time limit is shared by all queries, which are walked in order,
+    /// so when time runs out, the last queries may not contribute to the
+    /// recommendations. Each walk is always completed, so the limit can be
+    /// slightly exceeded.
+    ///
+    /// [`recommendations`]: struct.Recommender.html#method.recommendations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use std::time::Duration;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let queries = [RecommenderNode::Object(raid)];
+    ///
+    /// let recommendations = recommender.recommendations_with_time_budget(
+    ///     &queries,
+    ///     3,
+    ///     50000,
+    ///     Duration::from_millis(20),
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    /// assert!(recommendations.contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn recommendations_with_time_budget(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        max_duration: Duration,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> Vec<RecommenderNode<T>> {
+        let deadline = Instant::now() + max_duration;
+        let all_recommendations =
+            self.aggregated_recommendations(queries, max_total_steps, |_, q, steps| {
+                self.time_budget_recommendations_map(q, depth, steps, deadline, |from, to| {
+                    self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+                })
+                .0
+            });
+        self.rank_recommendations(&all_recommendations, |node| !queries.contains(node))
+    }
+
+    /// Accumulates the visits of the walks from a node until either
+    /// `max_total_steps` steps are taken or the `deadline` is reached,
+    /// returning the visits and the number of steps taken.
+    fn time_budget_recommendations_map(
+        &self,
+        from: &RecommenderNode<T>,
+        depth: u8,
+        max_total_steps: usize,
+        deadline: Instant,
+        weight_fun: impl Fn(&RecommenderNode<T>, &RecommenderNode<T>) -> f32,
+    ) -> (HashMap<RecommenderNode<T>, u32>, usize) {
+        let mut acc: HashMap<RecommenderNode<T>, u32> = HashMap::new();
+        let mut steps_acc = 0;
+        Recommender::accumulate_walks(
+            max_total_steps,
+            || {
+                if Instant::now() >= deadline {
+                    return Default::default();
+                }
+                self.graph.random_walk(from, depth, &weight_fun)
+            },
+            |visits| {
+                steps_acc += visits.len();
+                for visited in visits {
+                    *acc.entry(visited).or_insert(0) += 1;
+                }
+            },
+        );
+        (acc, steps_acc)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn time_limit_dominates() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..1000 {
+            recommender.tag_object(&obj, &format!("{}", obj % 10));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+        let query = RecommenderNode::Object(0);
+
+        let started = Instant::now();
+        let (counts, steps) = recommender.time_budget_recommendations_map(
+            &query,
+            5,
+            usize::MAX,
+            Instant::now() + Duration::from_millis(20),
+            |_, _| 1.0,
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(steps > 0);
+        assert_eq!(counts.values().map(|c| *c as usize).sum::<usize>(), steps);
+
+        let recommendations = recommender.recommendations_with_time_budget(
+            &[query],
+            5,
+            usize::MAX,
+            Duration::from_secs(0),
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(recommendations.is_empty());
+    }
+
+    #[test]
+    fn step_limit_dominates() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..1000 {
+            recommender.tag_object(&obj, &format!("{}", obj % 10));
+            recommender.tag_object(&obj, &format!("{}", obj % 7 + 10));
+        }
+        let query = RecommenderNode::Object(0);
+
+        let (_, steps) = recommender.time_budget_recommendations_map(
+            &query,
+            5,
+            1000,
+            Instant::now() + Duration::from_secs(60),
+            |_, _| 1.0,
+        );
+        assert!((1000..1005).contains(&steps));
+
+        let recommendations = recommender.recommendations_with_time_budget(
+            &[query],
+            5,
+            1000,
+            Duration::from_secs(60),
+            |_, _| 1.0,
+            |_, _| 1.0,
+        );
+        assert!(!recommendations.is_empty());
+    }
+}
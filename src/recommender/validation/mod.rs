@@ -0,0 +1,200 @@
+//! # Validation
+//!
+//! The `validation` module is a collection of utilities to find
+//! structural problems in a recommender.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Report with the structural problems found in a recommender.
+///
+/// The order of the elements in each list is unspecified.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ValidationReport<T> {
+    /// Objects without any tag.
+    pub isolated_objects: Vec<T>,
+    /// Tags without any object.
+    pub empty_tags: Vec<String>,
+    /// Names that are used both by a tag and an object.
+    pub colliding_names: Vec<String>,
+    /// Number of connected components of the graph.
+    pub components: usize,
+}
+
+impl<T> ValidationReport<T> {
+    /// Checks if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.isolated_objects.is_empty()
+            && self.empty_tags.is_empty()
+            && self.colliding_names.is_empty()
+            && self.components <= 1
+    }
+}
+
+impl<T: Eq + Clone + Hash + fmt::Display> Recommender<T> {
+    /// Checks the recommender for structural problems, such as objects
+    /// without tags, tags without objects, tags with the same name as an
+    /// object and disconnected components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    /// assert!(recommender.validate().is_valid());
+    ///
+    /// recommender.add_tag("Comedy");
+    /// let report = recommender.validate();
+    /// assert_eq!(report.empty_tags, vec![String::from("Comedy")]);
+    /// assert_eq!(report.components, 2);
+    /// ```
+    pub fn validate(&self) -> ValidationReport<T> {
+        let mut isolated_objects: Vec<T> = Vec::new();
+        let mut empty_tags: Vec<String> = Vec::new();
+        let mut object_names: HashSet<String> = HashSet::new();
+        let mut tag_names: HashSet<&String> = HashSet::new();
+
+        for node in self.graph.nodes() {
+            let isolated = self.graph.degree(node) == 0;
+            match node {
+                RecommenderNode::Object(obj) => {
+                    object_names.insert(obj.to_string());
+                    if isolated {
+                        isolated_objects.push(obj.clone());
+                    }
+                }
+                RecommenderNode::Tag(tag) => {
+                    tag_names.insert(tag);
+                    if isolated {
+                        empty_tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+
+        let colliding_names = tag_names
+            .into_iter()
+            .filter(|tag| object_names.contains(*tag))
+            .cloned()
+            .collect();
+
+        ValidationReport {
+            isolated_objects,
+            empty_tags,
+            colliding_names,
+            components: self.graph.connected_components().len(),
+        }
+    }
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Checks that the graph of the recommender is bipartite.
+    ///
+    /// This is always the case when the graph is only changed with
+    /// [`tag_object`](struct.Recommender.html#method.tag_object).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the graph has a cycle with an odd length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// recommender.tag_object(&String::from("The Raid"), "Action");
+    /// recommender.tag_object(&String::from("Rocky"), "Action");
+    /// recommender.assert_bipartite();
+    /// ```
+    pub fn assert_bipartite(&self) {
+        assert!(
+            self.graph.is_bipartite(),
+            "The recommender graph is not bipartite"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_structural_problems() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        recommender.tag_object(&String::from("The Raid"), "Action");
+        recommender.tag_object(&String::from("Rocky"), "Action");
+        recommender.tag_object(&String::from("Monty Python"), "Comedy");
+        recommender.tag_object(&String::from("Drama"), "Comedy");
+        recommender.add_object(&String::from("Lonely Movie"));
+        recommender.add_tag("Drama");
+        recommender.add_tag("Horror");
+
+        let report = recommender.validate();
+
+        assert!(!report.is_valid());
+        assert_eq!(report.isolated_objects, vec![String::from("Lonely Movie")]);
+        assert_eq!(
+            report
+                .empty_tags
+                .iter()
+                .cloned()
+                .collect::<HashSet<String>>(),
+            ["Drama", "Horror"].iter().map(|x| x.to_string()).collect()
+        );
+        assert_eq!(report.colliding_names, vec![String::from("Drama")]);
+        assert_eq!(report.components, 5);
+    }
+
+    #[test]
+    fn valid_recommender() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        assert!(recommender.validate().is_valid());
+
+        recommender.tag_object(&1, "Odd");
+        recommender.tag_object(&2, "Even");
+        recommender.tag_object(&2, "Prime");
+        recommender.tag_object(&3, "Odd");
+        recommender.tag_object(&3, "Prime");
+
+        let report = recommender.validate();
+        assert!(report.is_valid(), "{:?}", report);
+        assert_eq!(report.components, 1);
+    }
+
+    #[test]
+    fn bipartite_recommender() {
+        let mut recommender: Recommender<String> = Recommender::new();
+
+        recommender.tag_object(&String::from("The Raid"), "Action");
+        recommender.tag_object(&String::from("Rocky"), "Action");
+        recommender.tag_object(&String::from("Rocky"), "Drama");
+        assert!(recommender.graph.is_bipartite());
+        recommender.assert_bipartite();
+    }
+
+    #[test]
+    #[should_panic(expected = "not bipartite")]
+    fn object_to_object_edge() {
+        let mut recommender: Recommender<String> = Recommender::new();
+        let raid = RecommenderNode::Object(String::from("The Raid"));
+        let rocky = RecommenderNode::Object(String::from("Rocky"));
+
+        recommender.tag_object(&String::from("The Raid"), "Action");
+        recommender.tag_object(&String::from("Rocky"), "Action");
+        recommender.graph.add_edge(&raid, &rocky);
+
+        assert!(!recommender.graph.is_bipartite());
+        recommender.assert_bipartite();
+    }
+}
@@ -0,0 +1,242 @@
+//! # Visit Model
+//!
+//! The `visit_model` module is a collection of utilities to store the
+//! visit counts of a set of queries, so that they can be ranked again
+//! later without performing new random walks.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::str::FromStr;
+
+use super::Recommender;
+use super::RecommenderNode;
+
+/// Visit counts accumulated by the random walks of each query.
+///
+/// The counts are only valid while the graph does not change
+/// significantly, after that the model should be computed again.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct VisitModel<T: Eq + Hash> {
+    counts: HashMap<RecommenderNode<T>, HashMap<RecommenderNode<T>, u32>>,
+}
+
+impl<T: Eq + Clone + Hash> VisitModel<T> {
+    /// Visit counts of a query, if it is part of this model.
+    pub fn query_counts(
+        &self,
+        query: &RecommenderNode<T>,
+    ) -> Option<&HashMap<RecommenderNode<T>, u32>> {
+        self.counts.get(query)
+    }
+
+    /// Ranks the stored visit counts, returning an ordered sequence of
+    /// recommendations (with the first one being the "best" one).
+    ///
+    /// The contribution of each query is multiplied by its `query_weight`,
+    /// so the same model can be reused with different priors.
+    ///
+    /// The model is independent of the recommender, so its blocklist is
+    /// not applied. The results should be filtered again if needed.
+    pub fn recommendations(
+        &self,
+        query_weight: impl Fn(&RecommenderNode<T>) -> f64,
+    ) -> Vec<RecommenderNode<T>> {
+        let mut all_recommendations: HashMap<RecommenderNode<T>, f64> = HashMap::new();
+        for (query, counts) in self.counts.iter() {
+            let weight = query_weight(query);
+            for (key, value) in counts.iter() {
+                *all_recommendations.entry(key.clone()).or_insert(0.0) +=
+                    weight * (*value as f64).sqrt();
+            }
+        }
+
+        let scores = all_recommendations.iter().map(|(k, v)| (k, v * v));
+        Recommender::sort_recommendations(scores, |node| !self.counts.contains_key(node))
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect()
+    }
+}
+
+impl<T: Eq + Clone + Hash + fmt::Display> VisitModel<T> {
+    /// Writes this model to `writer`, one visit count per line.
+    ///
+    /// Names containing tabs or line breaks are not supported.
+    pub fn write_to(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for (query, counts) in self.counts.iter() {
+            for (node, count) in counts.iter() {
+                writeln!(
+                    writer,
+                    "{}\t{}\t{}",
+                    NodeFormat(query),
+                    NodeFormat(node),
+                    count
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Eq + Clone + Hash + FromStr> VisitModel<T> {
+    /// Reads a model written by [`write_to`](#method.write_to).
+    pub fn read_from(reader: impl io::BufRead) -> io::Result<VisitModel<T>> {
+        let mut counts: HashMap<RecommenderNode<T>, HashMap<RecommenderNode<T>, u32>> =
+            HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            if fields.len() != 5 {
+                return Err(invalid_data(&line));
+            }
+            let query = parse_node(fields[0], fields[1]).ok_or_else(|| invalid_data(&line))?;
+            let node = parse_node(fields[2], fields[3]).ok_or_else(|| invalid_data(&line))?;
+            let count = fields[4].parse::<u32>().map_err(|_| invalid_data(&line))?;
+            counts.entry(query).or_default().insert(node, count);
+        }
+        Ok(VisitModel { counts })
+    }
+}
+
+struct NodeFormat<'a, T>(&'a RecommenderNode<T>);
+
+impl<'a, T: fmt::Display> fmt::Display for NodeFormat<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            RecommenderNode::Tag(tag) => write!(f, "tag\t{}", tag),
+            RecommenderNode::Object(obj) => write!(f, "object\t{}", obj),
+        }
+    }
+}
+
+fn parse_node<T: FromStr>(kind: &str, name: &str) -> Option<RecommenderNode<T>> {
+    match kind {
+        "tag" => Some(RecommenderNode::Tag(String::from(name))),
+        "object" => name.parse::<T>().ok().map(RecommenderNode::Object),
+        _ => None,
+    }
+}
+
+fn invalid_data(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid visit model line: {}", line),
+    )
+}
+
+impl<T: Eq + Clone + Hash> Recommender<T> {
+    /// Performs the random walks of each query and stores the resulting
+    /// visit counts in a [`VisitModel`], which can be ranked (and persisted)
+    /// independently of this recommender.
+    ///
+    /// The steps are split among the queries in the same way as in
+    /// [`recommendations`](struct.Recommender.html#method.recommendations).
+    ///
+    /// [`VisitModel`]: visit_model/struct.VisitModel.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pixie_rust::recommender::Recommender;
+    /// use pixie_rust::recommender::RecommenderNode;
+    /// use pixie_rust::recommender::visit_model::VisitModel;
+    ///
+    /// let mut recommender: Recommender<String> = Recommender::new();
+    ///
+    /// let raid = String::from("The Raid");
+    /// let rocky = String::from("Rocky");
+    ///
+    /// recommender.tag_object(&raid, "Action");
+    /// recommender.tag_object(&rocky, "Action");
+    ///
+    /// let model = recommender.visit_model(
+    ///     &[RecommenderNode::Object(raid)],
+    ///     3,
+    ///     100,
+    ///     |_, _| 1.0,
+    ///     |_, _| 1.0
+    /// );
+    ///
+    /// let mut buffer: Vec<u8> = Vec::new();
+    /// model.write_to(&mut buffer).unwrap();
+    /// let model: VisitModel<String> = VisitModel::read_from(&buffer[..]).unwrap();
+    ///
+    /// assert!(model.recommendations(|_| 1.0).contains(&RecommenderNode::Object(rocky)));
+    /// ```
+    pub fn visit_model(
+        &self,
+        queries: &[RecommenderNode<T>],
+        depth: u8,
+        max_total_steps: usize,
+        object_to_tag_weight: impl Fn(&T, &String) -> f32,
+        tag_to_object_weight: impl Fn(&String, &T) -> f32,
+    ) -> VisitModel<T> {
+        let query_budgets = self.query_budgets(queries, max_total_steps);
+        let mut counts: HashMap<RecommenderNode<T>, HashMap<RecommenderNode<T>, u32>> =
+            HashMap::new();
+        for (q, max_steps) in queries.iter().zip(query_budgets) {
+            let query_counts = self.recommendations_map(q, depth, max_steps, |from, to| {
+                self.walk_weight(from, to, &object_to_tag_weight, &tag_to_object_weight)
+            });
+            let entry = counts.entry(q.clone()).or_default();
+            for (node, count) in query_counts {
+                *entry.entry(node).or_insert(0) += count;
+            }
+        }
+        VisitModel { counts }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip_visit_model() {
+        let mut recommender: Recommender<u32> = Recommender::new();
+        for obj in 0..20 {
+            recommender.tag_object(&obj, if obj % 2 == 0 { "Even" } else { "Odd" });
+            if obj % 3 == 0 {
+                recommender.tag_object(&obj, "Multiple of 3");
+            }
+        }
+
+        let queries = [RecommenderNode::Object(0), RecommenderNode::Object(1)];
+        let model = recommender.visit_model(&queries, 5, 2000, |_, _| 1.0, |_, _| 1.0);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        model.write_to(&mut buffer).unwrap();
+        let reloaded: VisitModel<u32> = VisitModel::read_from(&buffer[..]).unwrap();
+
+        assert_eq!(reloaded, model);
+        assert!(reloaded.query_counts(&queries[0]).is_some());
+
+        let mut counts: HashMap<RecommenderNode<u32>, HashMap<RecommenderNode<u32>, u32>> =
+            HashMap::new();
+        let query_counts = counts.entry(RecommenderNode::Object(0)).or_default();
+        for obj in 1..10 {
+            query_counts.insert(RecommenderNode::Object(obj), obj * obj);
+        }
+        let model = VisitModel { counts };
+        let mut buffer: Vec<u8> = Vec::new();
+        model.write_to(&mut buffer).unwrap();
+        let reloaded: VisitModel<u32> = VisitModel::read_from(&buffer[..]).unwrap();
+
+        let top_k = model.recommendations(|_| 2.0);
+        assert_eq!(reloaded.recommendations(|_| 2.0), top_k);
+        assert_eq!(top_k[0], RecommenderNode::Object(9));
+        assert_eq!(top_k.len(), 9);
+    }
+
+    #[test]
+    fn reject_invalid_lines() {
+        let result: io::Result<VisitModel<u32>> =
+            VisitModel::read_from("object\t1\ttag\tEven\tmany\n".as_bytes());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}